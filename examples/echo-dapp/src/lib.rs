@@ -1,9 +1,38 @@
-use trolley::Rollup;
+use trolley::{App, Finish, Input, Rollup, RollupError};
 use types::Notice;
 
-pub fn run(mut rollup: impl Rollup) -> ! {
-    loop {
-        let i = rollup.next_input();
-        rollup.emit_notice(&Notice { payload: i.payload });
+/// Payload of an input that is echoed, and also reported, before being rejected.
+pub const REJECT: &[u8] = b"reject";
+
+/// Payload of an input that is echoed before the dapp panics on it.
+pub const PANIC: &[u8] = b"panic";
+
+pub struct Echo;
+
+impl App for Echo {
+    type Error = RollupError;
+
+    fn advance<R: Rollup>(&mut self, rollup: &mut R, input: Input) -> Result<Finish, RollupError> {
+        rollup.emit_notice(&Notice {
+            payload: input.payload.clone(),
+        })?;
+
+        if input.payload.as_ref() == PANIC {
+            panic!("echo dapp told to panic");
+        }
+        if input.payload.as_ref() == REJECT {
+            rollup.emit_report(REJECT)?;
+            return Ok(Finish::Reject);
+        }
+        Ok(Finish::Accept)
     }
+
+    fn inspect<R: Rollup>(&self, rollup: &mut R, payload: &[u8]) -> Result<Finish, RollupError> {
+        rollup.emit_report(payload)?;
+        Ok(Finish::Accept)
+    }
+}
+
+pub fn run(rollup: impl Rollup) -> Result<(), RollupError> {
+    trolley::run(Echo, rollup)
 }
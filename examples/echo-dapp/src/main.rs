@@ -1,8 +1,9 @@
-use echo_lib;
 use trolley::cmt;
 
 fn main() {
     println!("Hello, World!");
-    let rollup = cmt::RollupCmt::new();
-    echo_lib::run(rollup);
+    let mut rollup = cmt::RollupCmt::try_new().expect("failed to instantiate rollup");
+    trolley::panic::forward_panics(&mut rollup, |rollup| {
+        echo_lib::run(rollup).expect("echo dapp failed")
+    });
 }
@@ -0,0 +1,74 @@
+use alloy_primitives::Address;
+use trolley::{
+    mock::{input, RollupMock},
+    Finish,
+};
+
+#[test]
+fn echoes_advances_as_notices_and_inspects_as_reports() {
+    let mut mock = RollupMock::with_inputs([
+        input(Address::ZERO, b"hello"),
+        input(Address::ZERO, b"world"),
+    ]);
+    mock.push_inspect(b"query");
+
+    assert_eq!(echo_lib::run(&mut mock), Ok(()));
+
+    let notices: Vec<_> = mock.notices().iter().map(|n| n.payload.to_vec()).collect();
+    assert_eq!(notices, vec![b"hello".to_vec(), b"world".to_vec()]);
+    assert_eq!(mock.reports(), vec![b"query".as_slice()]);
+    assert!(mock
+        .processed()
+        .iter()
+        .all(|p| p.finish == Some(Finish::Accept)));
+}
+
+#[test]
+fn rejected_inputs_keep_their_reports_only() {
+    let mut mock = RollupMock::with_inputs([
+        input(Address::ZERO, b"hello"),
+        input(Address::ZERO, b"reject"),
+        input(Address::ZERO, b"world"),
+    ]);
+
+    assert_eq!(echo_lib::run(&mut mock), Ok(()));
+
+    let notices: Vec<_> = mock.notices().iter().map(|n| n.payload.to_vec()).collect();
+    assert_eq!(notices, vec![b"hello".to_vec(), b"world".to_vec()]);
+    assert_eq!(mock.reports(), vec![b"reject".as_slice()]);
+
+    let finishes: Vec<_> = mock.processed().iter().map(|p| p.finish).collect();
+    assert_eq!(
+        finishes,
+        vec![
+            Some(Finish::Accept),
+            Some(Finish::Reject),
+            Some(Finish::Accept)
+        ]
+    );
+}
+
+#[test]
+fn panics_are_forwarded_as_exceptions() {
+    let mut mock = RollupMock::with_inputs([
+        input(Address::ZERO, b"hello"),
+        input(Address::ZERO, b"panic"),
+        input(Address::ZERO, b"world"),
+    ]);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        trolley::panic::forward_panics(&mut mock, |rollup| echo_lib::run(rollup))
+    }));
+    assert!(result.is_err());
+
+    let exception = String::from_utf8(mock.exception().unwrap().to_vec()).unwrap();
+    assert!(
+        exception.contains("echo dapp told to panic"),
+        "{}",
+        exception
+    );
+
+    // the panicking input never finished, and the last one was never read
+    let finishes: Vec<_> = mock.processed().iter().map(|p| p.finish).collect();
+    assert_eq!(finishes, vec![Some(Finish::Accept), None]);
+}
@@ -10,12 +10,133 @@ pub fn test_echo() -> testsi::TestResult {
 
     // Input 0
     let input = testsi::InputBuilder::from_address(Address::ZERO).with_payload(&"hello");
-    let (outputs, _reports) = machine.advance_state(input)?;
+    let result = machine.advance_state(input)?;
+    assert!(result.is_accepted());
+    assert_eq!(result.input_index, 0);
     assert_eq!(
-        outputs[0].expect_notice().payload.as_ref(),
+        result.outputs[0].expect_notice().payload.as_ref(),
         "hello".as_bytes()
     );
-    assert_eq!(outputs.notices()[0].payload.as_ref(), "hello".as_bytes());
+    assert_eq!(
+        result.outputs.notices()[0].payload.as_ref(),
+        "hello".as_bytes()
+    );
+
+    // Input 1
+    let input = testsi::InputBuilder::from_address(Address::ZERO).with_payload(&"world");
+    let result = machine.advance_state(input)?;
+    assert!(result.is_accepted());
+    assert_eq!(result.input_index, 1);
+    assert!(result.reports.is_empty());
+
+    Ok(())
+}
+
+#[testsi::test_dapp(kind("dapp"))]
+pub fn test_inspect() -> testsi::TestResult {
+    let mut machine = testsi::MachineBuilder::load_from("./echo")
+        .at_chain(31337)
+        .try_build()?;
+
+    let result = machine.inspect(&"hello")?;
+    assert!(result.is_accepted());
+    assert_eq!(result.reports.len(), 1);
+    assert_eq!(result.reports[0].as_str(), Some("hello"));
+
+    // inspecting leaves the machine untouched, so it advances as usual afterwards
+    let input = testsi::InputBuilder::from_address(Address::ZERO).with_payload(&"world");
+    let result = machine.advance_state(input)?;
+    assert_eq!(
+        result.outputs.notices()[0].payload.as_ref(),
+        "world".as_bytes()
+    );
+    assert_eq!(
+        machine.inspect(&"again")?.reports[0].as_str(),
+        Some("again")
+    );
+
+    Ok(())
+}
+
+#[testsi::test_dapp(kind("dapp"))]
+pub fn test_reject() -> testsi::TestResult {
+    let mut machine = testsi::MachineBuilder::load_from("./echo")
+        .at_chain(31337)
+        .try_build()?;
+
+    let input = testsi::InputBuilder::from_address(Address::ZERO).with_payload(&"hello");
+    let accepted = machine.advance_state(input)?;
+    assert!(accepted.is_accepted());
+
+    // the notice is dropped and the machine reverted, but the report is kept
+    let input = testsi::InputBuilder::from_address(Address::ZERO).with_payload(&"reject");
+    let result = machine.advance_state(input)?;
+    assert_eq!(result.status, testsi::FinishStatus::Rejected);
+    assert_eq!(result.input_index, 1);
+    assert!(result.outputs.list().is_empty());
+    assert_eq!(result.reports.len(), 1);
+    assert_eq!(result.reports[0].as_str(), Some("reject"));
+    assert_eq!(result.root_hash, accepted.root_hash);
+
+    // the rejected input still took its index
+    let input = testsi::InputBuilder::from_address(Address::ZERO).with_payload(&"world");
+    let result = machine.advance_state(input)?;
+    assert!(result.is_accepted());
+    assert_eq!(result.input_index, 2);
+    assert_eq!(
+        result.outputs.notices()[0].payload.as_ref(),
+        "world".as_bytes()
+    );
+
+    Ok(())
+}
+
+#[testsi::test_dapp(kind("dapp"))]
+pub fn test_panic() -> testsi::TestResult {
+    let mut machine = testsi::MachineBuilder::load_from("./echo")
+        .at_chain(31337)
+        .try_build()?;
+
+    // `forward_panics` raises the panic message as an exception, which drops the notice
+    let input = testsi::InputBuilder::from_address(Address::ZERO).with_payload(&"panic");
+    let result = machine.advance_state(input)?;
+    let testsi::FinishStatus::Exception(payload) = &result.status else {
+        panic!("expected an exception, got {:?}", result.status);
+    };
+    let message = String::from_utf8_lossy(payload);
+    assert!(message.contains("echo dapp told to panic"), "{}", message);
+    assert!(result.outputs.list().is_empty());
+
+    // the dapp is gone after an exception
+    let input = testsi::InputBuilder::from_address(Address::ZERO).with_payload(&"hello");
+    assert!(matches!(
+        machine.advance_state(input),
+        Err(testsi::Error::Dead)
+    ));
+    assert!(matches!(
+        machine.inspect(&"hello"),
+        Err(testsi::Error::Dead)
+    ));
+
+    Ok(())
+}
+
+#[testsi::test_dapp(kind("dapp"))]
+pub fn test_mcycle_limit() -> testsi::TestResult {
+    let mut machine = testsi::MachineBuilder::load_from("./echo")
+        .at_chain(31337)
+        .with_mcycle_limit(1)
+        .try_build()?;
+
+    let input = testsi::InputBuilder::from_address(Address::ZERO).with_payload(&"hello");
+    assert!(matches!(
+        machine.advance_state(input.clone()),
+        Err(testsi::Error::McycleLimit { limit: 1, .. })
+    ));
+    assert!(matches!(
+        machine.advance_state(input),
+        Err(testsi::Error::Dead)
+    ));
 
     Ok(())
 }
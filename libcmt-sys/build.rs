@@ -1,35 +1,161 @@
-use bytes::Buf;
 use hex_literal::hex;
 use sha2::{Digest, Sha512};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use xz2::read::XzDecoder;
 
-const LIBCMT_URL: &str = "https://github.com/cartesi/machine-emulator-tools/releases/download/v0.16.1/libcmt-dev-riscv64-cross-v0.16.1.deb";
-const LIBCMT_CHECKSUM: [u8; 64] = hex!("4eafbc8987e1f34d2ec40eb6c90f75ea269041812993227598c88086258189aeef3bdb42790a7504d4f0204b75764aa507002ca5c1433566382f5e531ac8901a");
+const LIBCMT_DEFAULT_VERSION: &str = "v0.16.1";
+// Releases pinned with the SHA-512 of their `.deb`, each with its bindings committed as
+// `src/bindings/<version>.rs`. Any other release needs the `bindgen` feature, and LIBCMT_SHA512
+// to be downloaded.
+const LIBCMT_RELEASES: &[(&str, [u8; 64])] = &[(
+    "v0.16.1",
+    hex!("4eafbc8987e1f34d2ec40eb6c90f75ea269041812993227598c88086258189aeef3bdb42790a7504d4f0204b75764aa507002ca5c1433566382f5e531ac8901a"),
+)];
+
+// Sources of `sys-utils/libcmt` in machine-emulator-tools, relative to its root. The io driver
+// is picked separately: the ioctl one for the machine, or the file-based mock for the host.
+#[cfg(feature = "build-from-source")]
+const LIBCMT_SOURCES: &[&str] = &[
+    "src/abi.c",
+    "src/buf.c",
+    "src/keccak.c",
+    "src/merkle.c",
+    "src/rollup.c",
+    "src/util.c",
+];
+#[cfg(feature = "build-from-source")]
+const LIBCMT_IO_SOURCE: &str = "src/io.c";
+#[cfg(feature = "build-from-source")]
+const LIBCMT_IO_MOCK_SOURCE: &str = "src/io-mock.c";
 
 fn main() {
-    download_libcmt();
+    let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+
+    // Where libcmt comes from, in order of precedence:
+    //  - the `host-mock` feature: the host mock, compiled as with `build-from-source`;
+    //  - LIBCMT_DIR: a prefix with `lib/libcmt.a` and `include/libcmt/`;
+    //  - the `build-from-source` feature: compiled from the sources in LIBCMT_SRC, or else the
+    //    machine-emulator-tools checkout in `vendor/`;
+    //  - LIBCMT_DEB: a local copy of the release `.deb`;
+    //  - the `download` feature: the release `.deb`, fetched from GitHub.
+    // LIBCMT_VERSION picks the release to use, and only labels the library for local builds.
+    let version = std::env::var("LIBCMT_VERSION").ok();
+    let (headers_path, version) = if cfg!(feature = "host-mock") {
+        let src = libcmt_src();
+        build_libcmt(&src, true);
+        (src.join("include/libcmt"), version)
+    } else if let Some(dir) = env_path("LIBCMT_DIR") {
+        link_libcmt(&dir.join("lib"));
+        (dir.join("include/libcmt"), version)
+    } else if cfg!(feature = "build-from-source") {
+        let src = libcmt_src();
+        build_libcmt(&src, false);
+        (src.join("include/libcmt"), version)
+    } else {
+        let version = version.unwrap_or_else(|| LIBCMT_DEFAULT_VERSION.to_string());
+        let checksum = libcmt_checksum(&version);
+        let data = match env_path("LIBCMT_DEB") {
+            Some(deb) => std::fs::read(&deb).expect("error reading LIBCMT_DEB"),
+            None => download_libcmt(&version),
+        };
+        unpack_libcmt(&data, &checksum, &out_path);
+        link_libcmt(&out_path.join("usr/riscv64-linux-gnu/lib/"));
+        (
+            out_path.join("usr/riscv64-linux-gnu/include/libcmt/"),
+            Some(version),
+        )
+    };
+
+    println!(
+        "cargo:rustc-env=LIBCMT_VERSION={}",
+        version.as_deref().unwrap_or("unknown")
+    );
+
+    let bindings_path = bindings(&headers_path, version.as_deref(), &out_path);
+    println!(
+        "cargo:rustc-env=LIBCMT_BINDINGS={}",
+        bindings_path.display()
+    );
+    detect_features(&bindings_path);
+
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=wrapper.h");
+    println!("cargo:rerun-if-env-changed=LIBCMT_DIR");
+    println!("cargo:rerun-if-env-changed=LIBCMT_SRC");
+    println!("cargo:rerun-if-env-changed=LIBCMT_DEB");
+    println!("cargo:rerun-if-env-changed=LIBCMT_VERSION");
+    println!("cargo:rerun-if-env-changed=LIBCMT_SHA512");
 }
 
-fn download_libcmt() {
-    let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap());
-    let lib_path = out_path.join("usr/riscv64-linux-gnu/lib/");
-    let headers_path = out_path.join("usr/riscv64-linux-gnu/include/libcmt/");
+fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name).map(PathBuf::from)
+}
 
-    // get
-    let data = reqwest::blocking::get(LIBCMT_URL)
+fn libcmt_src() -> PathBuf {
+    env_path("LIBCMT_SRC").unwrap_or_else(|| {
+        PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+            .join("vendor/machine-emulator-tools/sys-utils/libcmt")
+    })
+}
+
+fn libcmt_checksum(version: &str) -> [u8; 64] {
+    if let Some((_, checksum)) = LIBCMT_RELEASES.iter().find(|(v, _)| *v == version) {
+        return *checksum;
+    }
+    // an unpinned release has no committed bindings to go with it
+    if !cfg!(feature = "bindgen") {
+        panic!(
+            "libcmt {} is not pinned, enable the `bindgen` feature and set LIBCMT_SHA512 to use it",
+            version
+        );
+    }
+
+    if let Ok(checksum) = std::env::var("LIBCMT_SHA512") {
+        let checksum = checksum.trim();
+        assert_eq!(checksum.len(), 128, "LIBCMT_SHA512 must be 128 hex digits");
+
+        let mut bytes = [0; 64];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&checksum[2 * i..2 * i + 2], 16)
+                .expect("LIBCMT_SHA512 must be 128 hex digits");
+        }
+        return bytes;
+    }
+
+    panic!(
+        "no checksum pinned for libcmt {}, set LIBCMT_SHA512",
+        version
+    )
+}
+
+#[cfg(feature = "download")]
+fn download_libcmt(version: &str) -> Vec<u8> {
+    let url = format!(
+        "https://github.com/cartesi/machine-emulator-tools/releases/download/{0}/libcmt-dev-riscv64-cross-{0}.deb",
+        version
+    );
+    reqwest::blocking::get(url)
         .expect("error downloading libcmt")
         .bytes()
-        .expect("error getting libcmt request body");
+        .expect("error getting libcmt request body")
+        .to_vec()
+}
 
+#[cfg(not(feature = "download"))]
+fn download_libcmt(_version: &str) -> Vec<u8> {
+    panic!(
+        "libcmt not found: set LIBCMT_DIR or LIBCMT_DEB, or enable the `build-from-source` or `download` feature"
+    )
+}
+
+fn unpack_libcmt(data: &[u8], checksum: &[u8; 64], out_path: &Path) {
     // checksum
     let mut hasher = Sha512::new();
-    hasher.update(&data);
+    hasher.update(data);
     let result = hasher.finalize();
-    assert_eq!(result[..], LIBCMT_CHECKSUM, "libcmt checksum failed");
+    assert_eq!(result[..], checksum[..], "libcmt checksum failed");
 
-    let mut archive = ar::Archive::new(data.reader());
+    let mut archive = ar::Archive::new(data);
     let entry = loop {
         if let Some(Ok(entry)) = archive.next_entry() {
             if entry.header().identifier() == "data.tar.xz".as_bytes() {
@@ -44,19 +170,99 @@ fn download_libcmt() {
     let xz = XzDecoder::new(entry);
     let mut inner_archive = tar::Archive::new(xz);
     inner_archive
-        .unpack(&out_path)
+        .unpack(out_path)
         .expect("failed to unpack libcmt");
+}
 
-    // compiler flags
+fn link_libcmt(lib_path: &Path) {
     println!("cargo:rustc-link-search={}", lib_path.to_str().unwrap());
     println!("cargo:rustc-link-lib=static=cmt");
+}
+
+#[cfg(feature = "build-from-source")]
+fn build_libcmt(src: &Path, mock: bool) {
+    assert!(
+        src.join("include/libcmt/rollup.h").exists(),
+        "libcmt sources not found at {}, check out machine-emulator-tools in `vendor/` or set LIBCMT_SRC",
+        src.display()
+    );
+
+    let io_source = if mock {
+        LIBCMT_IO_MOCK_SOURCE
+    } else {
+        LIBCMT_IO_SOURCE
+    };
+    let sources: Vec<_> = LIBCMT_SOURCES
+        .iter()
+        .chain([&io_source])
+        .map(|s| src.join(s))
+        .collect();
+
+    for source in &sources {
+        println!("cargo:rerun-if-changed={}", source.display());
+    }
+
+    cc::Build::new()
+        .files(sources)
+        .include(src.join("include"))
+        .compile("cmt");
+}
 
+#[cfg(not(feature = "build-from-source"))]
+fn build_libcmt(_src: &Path, _mock: bool) {
+    unreachable!("libcmt is only built with the `build-from-source` feature")
+}
+
+// Older releases lack some of the API, which `lib.rs` then stubs out.
+fn detect_features(bindings_path: &Path) {
+    let bindings = std::fs::read_to_string(bindings_path).expect("error reading libcmt bindings");
+
+    println!("cargo:rustc-check-cfg=cfg(libcmt_delegate_call_voucher)");
+    if bindings.contains("fn cmt_rollup_emit_delegate_call_voucher(") {
+        println!("cargo:rustc-cfg=libcmt_delegate_call_voucher");
+    }
+}
+
+#[cfg(feature = "bindgen")]
+fn bindings(headers_path: &Path, _version: Option<&str>, out_path: &Path) -> PathBuf {
+    // `wrapper.h` includes every public header as `<libcmt/...>`
+    let include_path = headers_path.parent().unwrap();
     let libcmt_bindings = bindgen::Builder::default()
-        .header(headers_path.join("rollup.h").to_str().unwrap())
+        .header("wrapper.h")
+        .clang_arg(format!("-I{}", include_path.to_str().unwrap()))
+        .allowlist_item("cmt_.*")
+        .allowlist_item("HTIF_.*")
         .generate()
         .expect("Unable to generate libcmt bindings");
 
+    let bindings_path = out_path.join("bindings.rs");
     libcmt_bindings
-        .write_to_file(out_path.join("bindings.rs"))
+        .write_to_file(&bindings_path)
         .expect("Couldn't write libcmt bindings");
+    bindings_path
+}
+
+// Without the `bindgen` feature, the bindings committed for the release are used as is.
+#[cfg(not(feature = "bindgen"))]
+fn bindings(_headers_path: &Path, version: Option<&str>, _out_path: &Path) -> PathBuf {
+    let version = version.expect(
+        "set LIBCMT_VERSION to the release of the local libcmt, or enable the `bindgen` feature",
+    );
+    assert!(
+        LIBCMT_RELEASES.iter().any(|(v, _)| *v == version),
+        "libcmt {} is not pinned, enable the `bindgen` feature to use it",
+        version
+    );
+
+    let bindings_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join(format!("src/bindings/{}.rs", version));
+    assert!(
+        bindings_path.exists(),
+        "no bindings committed for libcmt {0}, enable the `bindgen` feature, or generate them with \
+         `LIBCMT_VERSION={0} LIBCMT_BLESS=1 cargo test -p libcmt-sys --features bindgen`",
+        version
+    );
+
+    println!("cargo:rerun-if-changed={}", bindings_path.display());
+    bindings_path
 }
@@ -2,4 +2,24 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
-include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+// generated by bindgen into OUT_DIR, or committed in `src/bindings/<version>.rs`
+include!(env!("LIBCMT_BINDINGS"));
+
+/// Stands in for releases of libcmt without DELEGATECALL vouchers, failing with `-ENOSYS`.
+///
+/// # Safety
+///
+/// Same contract as the libcmt function, although none of the pointers are read.
+#[cfg(not(libcmt_delegate_call_voucher))]
+pub unsafe extern "C" fn cmt_rollup_emit_delegate_call_voucher(
+    _me: *mut cmt_rollup_t,
+    _address: *const cmt_abi_address_t,
+    _data: *const cmt_abi_bytes_t,
+    _index: *mut u64,
+) -> ::std::os::raw::c_int {
+    -38
+}
+
+/// The libcmt release this crate was built against, from `LIBCMT_VERSION` at build time, or
+/// `"unknown"` for a local build that did not set it.
+pub const LIBCMT_VERSION: &str = env!("LIBCMT_VERSION");
@@ -0,0 +1,44 @@
+//! Checks that the bindings committed for the release match the ones bindgen generates from its
+//! headers. Run with `cargo test -p libcmt-sys --features bindgen`, and set `LIBCMT_BLESS=1` to
+//! write `src/bindings/<version>.rs` from the generated ones when pinning a new release.
+#![cfg(feature = "bindgen")]
+
+#[test]
+fn committed_bindings_are_up_to_date() {
+    assert_ne!(
+        libcmt_sys::LIBCMT_VERSION,
+        "unknown",
+        "set LIBCMT_VERSION to the release being checked"
+    );
+
+    let generated =
+        std::fs::read_to_string(env!("LIBCMT_BINDINGS")).expect("error reading generated bindings");
+    let committed_path = format!(
+        "{}/src/bindings/{}.rs",
+        env!("CARGO_MANIFEST_DIR"),
+        libcmt_sys::LIBCMT_VERSION
+    );
+
+    if std::env::var_os("LIBCMT_BLESS").is_some() {
+        std::fs::create_dir_all(concat!(env!("CARGO_MANIFEST_DIR"), "/src/bindings"))
+            .expect("error creating src/bindings");
+        std::fs::write(&committed_path, &generated).expect("error writing committed bindings");
+        return;
+    }
+
+    let committed = std::fs::read_to_string(&committed_path)
+        .unwrap_or_else(|_| panic!("no bindings committed at {}", committed_path));
+    // bindgen stamps its own version on the first line, which would make the check flaky
+    assert!(
+        without_stamp(&committed) == without_stamp(&generated),
+        "{} is out of date, rerun with LIBCMT_BLESS=1 to update it",
+        committed_path
+    );
+}
+
+fn without_stamp(bindings: &str) -> &str {
+    bindings
+        .strip_prefix("/* automatically generated by rust-bindgen")
+        .and_then(|s| s.split_once('\n'))
+        .map_or(bindings, |(_, rest)| rest)
+}
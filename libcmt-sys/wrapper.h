@@ -0,0 +1,6 @@
+#include <libcmt/abi.h>
+#include <libcmt/buf.h>
+#include <libcmt/io.h>
+#include <libcmt/keccak.h>
+#include <libcmt/merkle.h>
+#include <libcmt/rollup.h>
@@ -0,0 +1,38 @@
+use cartesi_machine::errors::MachineError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Machine(#[from] MachineError),
+
+    #[error("the machine failed at mcycle {mcycle}")]
+    Failed { mcycle: u64 },
+
+    #[error("the machine halted at mcycle {mcycle}")]
+    Halted { mcycle: u64 },
+
+    #[error("the input ran past its limit of {limit} mcycles, at mcycle {mcycle}")]
+    McycleLimit { limit: u64, mcycle: u64 },
+
+    #[error("unknown break reason {reason} at mcycle {mcycle}")]
+    UnknownBreakReason { reason: u32, mcycle: u64 },
+
+    #[error("unknown manual yield reason {reason} (length {length}) at mcycle {mcycle}")]
+    UnknownManualYield {
+        reason: u32,
+        length: u64,
+        mcycle: u64,
+    },
+
+    #[error("unknown automatic yield reason {reason} (length {length}) at mcycle {mcycle}")]
+    UnknownAutomaticYield {
+        reason: u32,
+        length: u64,
+        mcycle: u64,
+    },
+
+    #[error("the machine no longer takes requests after an exception or error")]
+    Dead,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -1,7 +1,9 @@
+pub mod error;
 pub mod machine;
 pub mod test_runner;
 pub mod types;
 
+pub use error::{Error, Result};
 pub use machine::{Machine, MachineBuilder};
 pub use test_runner::*;
 pub use types::*;
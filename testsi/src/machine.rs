@@ -1,18 +1,22 @@
-use crate::types::{InputBuilder, OutputsForInput};
+use crate::{
+    error::{Error, Result},
+    types::{AdvanceResult, FinishStatus, InputBuilder, InspectResult, OutputsForInput, Report},
+};
 
 use alloy_primitives::{Address, U256};
 use cartesi_machine::configuration::MachineConfigRef;
-use std::{ops::ControlFlow, path::PathBuf};
-
-type MachineError = cartesi_machine::errors::MachineError;
-type Result<T> = std::result::Result<T, MachineError>;
-type Report = Vec<u8>;
+use std::{
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 pub struct MachineBuilder {
     cartesi_machine_path: PathBuf,
     chain_id: usize,
     dapp_address: Address,
     input_index: usize,
+    mcycle_limit: Option<u64>,
     no_console_putchar: bool,
 }
 
@@ -23,6 +27,7 @@ impl MachineBuilder {
             chain_id: 1,
             dapp_address: Address::ZERO,
             input_index: 0,
+            mcycle_limit: None,
             no_console_putchar: true,
         }
     }
@@ -42,6 +47,12 @@ impl MachineBuilder {
         self
     }
 
+    /// Fails requests that take more than `mcycle_limit` cycles with [`Error::McycleLimit`].
+    pub fn with_mcycle_limit(mut self, mcycle_limit: u64) -> MachineBuilder {
+        self.mcycle_limit = Some(mcycle_limit);
+        self
+    }
+
     pub fn no_console_putchar(mut self, no_console_putchar: bool) -> MachineBuilder {
         self.no_console_putchar = no_console_putchar;
         self
@@ -50,21 +61,26 @@ impl MachineBuilder {
     pub fn try_build(self) -> Result<Machine> {
         Machine::try_new(self)
     }
+
+    fn load(&self, path: &Path) -> Result<cartesi_machine::Machine> {
+        let runtime_config = cartesi_machine::configuration::RuntimeConfig::default()
+            .no_console_putchar(self.no_console_putchar);
+
+        Ok(cartesi_machine::Machine::load(path, runtime_config)?)
+    }
 }
 
 pub struct Machine {
     cartesi_machine: cartesi_machine::machine::Machine,
     builder: MachineBuilder,
+    dead: bool,
 }
 
 impl Machine {
     pub fn try_new(builder: MachineBuilder) -> Result<Self> {
-        let runtime_config = cartesi_machine::configuration::RuntimeConfig::default()
-            .no_console_putchar(builder.no_console_putchar);
-
         // Instantiate Machine
         let cartesi_machine = {
-            let cm = cartesi_machine::Machine::load(&builder.cartesi_machine_path, runtime_config)?;
+            let cm = builder.load(&builder.cartesi_machine_path)?;
             let c = cm.initial_config()?;
             sanity_check_cm_config(&c);
             cm
@@ -73,19 +89,32 @@ impl Machine {
         Ok(Self {
             cartesi_machine,
             builder,
+            dead: false,
         })
     }
 
-    pub fn advance_state(
-        &mut self,
-        input: InputBuilder,
-    ) -> Result<(OutputsForInput, Vec<Vec<u8>>)> {
+    /// Sends an advance request and runs the machine until it accepts or rejects it. A rejected
+    /// input is reverted as the node does: its outputs are discarded and the machine is restored
+    /// to its state before the input, but the reports are kept. After an exception, the outputs
+    /// are discarded too and the machine fails every later request with [`Error::Dead`], as it
+    /// does after any error while running the input.
+    ///
+    /// The state to revert to is taken by storing the whole machine to a temporary directory
+    /// before every input, accepted or not, and loading it back on a reject, so every call costs
+    /// a full machine snapshot on disk, as [`Machine::inspect`] does.
+    pub fn advance_state(&mut self, input: InputBuilder) -> Result<AdvanceResult> {
+        self.check_alive()?;
+
+        let input_index = self.builder.input_index;
         let encoded_input = input.encode(
             self.builder.chain_id,
-            U256::from(self.builder.input_index),
+            U256::from(input_index),
             self.builder.dapp_address,
         );
 
+        let mcycle_start = self.cartesi_machine.read_mcycle()?;
+        let snapshot = Snapshot::take(&self.cartesi_machine)?;
+
         self.cartesi_machine.send_cmio_response(
             cartesi_machine::htif::fromhost::ADVANCE_STATE,
             &encoded_input,
@@ -94,85 +123,212 @@ impl Machine {
         let mut outputs = OutputsForInput::default();
         let mut reports = Vec::new();
 
-        loop {
-            match run_machine_increment(&mut self.cartesi_machine, &mut outputs, &mut reports)? {
-                ControlFlow::Continue(_) => continue,
-                ControlFlow::Break(_) => break,
+        let status = match run_until_manual_yield(
+            &mut self.cartesi_machine,
+            self.builder.mcycle_limit,
+            &mut outputs,
+            &mut reports,
+        ) {
+            Ok(status) => status,
+            Err(e) => {
+                self.dead = true;
+                return Err(e);
+            }
+        };
+        let mcycles = self.cartesi_machine.read_mcycle()? - mcycle_start;
+
+        // the input takes its index whatever its fate, as on chain
+        self.builder.input_index += 1;
+
+        match status {
+            FinishStatus::Accepted => (),
+            FinishStatus::Rejected => {
+                // without the reverted machine, the one left has the rejected input applied
+                self.cartesi_machine = match self.builder.load(&snapshot.dir) {
+                    Ok(cartesi_machine) => cartesi_machine,
+                    Err(e) => {
+                        self.dead = true;
+                        return Err(e);
+                    }
+                };
+                outputs = OutputsForInput::default();
+            }
+            FinishStatus::Exception(_) => {
+                self.dead = true;
+                outputs = OutputsForInput::default();
             }
         }
 
-        Ok((outputs, reports))
+        Ok(AdvanceResult {
+            status,
+            outputs,
+            reports,
+            input_index,
+            mcycles,
+            root_hash: self.cartesi_machine.get_root_hash()?,
+        })
+    }
+
+    /// Runs an inspect request on a fork of the machine, which is discarded afterwards, and
+    /// returns how the dapp finished it along with the reports it emitted.
+    ///
+    /// The fork is made by storing the whole machine to a temporary directory and loading it
+    /// back, so every call costs a full machine snapshot on disk.
+    pub fn inspect<T: AsRef<[u8]>>(&self, payload: &T) -> Result<InspectResult> {
+        self.check_alive()?;
+
+        let snapshot = Snapshot::take(&self.cartesi_machine)?;
+        let mut fork = self.builder.load(&snapshot.dir)?;
+
+        fork.send_cmio_response(
+            cartesi_machine::htif::fromhost::INSPECT_STATE,
+            payload.as_ref(),
+        )?;
+
+        let mut outputs = OutputsForInput::default();
+        let mut reports = Vec::new();
+
+        let status = run_until_manual_yield(
+            &mut fork,
+            self.builder.mcycle_limit,
+            &mut outputs,
+            &mut reports,
+        )?;
+
+        Ok(InspectResult { status, reports })
     }
 
-    // TODO
-    pub fn inspect(&self) -> Result<()> {
-        todo!()
+    fn check_alive(&self) -> Result<()> {
+        if self.dead {
+            Err(Error::Dead)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A copy of the machine stored in a temporary directory, removed on drop.
+struct Snapshot {
+    dir: PathBuf,
+}
+
+impl Snapshot {
+    fn take(cartesi_machine: &cartesi_machine::Machine) -> Result<Self> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let snapshot = Self {
+            dir: std::env::temp_dir().join(format!(
+                "testsi-{}-{}",
+                std::process::id(),
+                COUNT.fetch_add(1, Ordering::Relaxed)
+            )),
+        };
+        cartesi_machine.store(&snapshot.dir)?;
+
+        Ok(snapshot)
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn run_until_manual_yield(
+    cartesi_machine: &mut cartesi_machine::machine::Machine,
+    mcycle_limit: Option<u64>,
+    outputs: &mut OutputsForInput,
+    reports: &mut Vec<Report>,
+) -> Result<FinishStatus> {
+    let mcycle_end = match mcycle_limit {
+        Some(limit) => cartesi_machine.read_mcycle()?.saturating_add(limit),
+        None => u64::MAX,
+    };
+
+    loop {
+        if let ControlFlow::Break(status) =
+            run_machine_increment(cartesi_machine, mcycle_end, mcycle_limit, outputs, reports)?
+        {
+            return Ok(status);
+        }
     }
 }
 
 fn run_machine_increment(
     cartesi_machine: &mut cartesi_machine::machine::Machine,
+    mcycle_end: u64,
+    mcycle_limit: Option<u64>,
     outputs: &mut OutputsForInput,
     reports: &mut Vec<Report>,
-) -> Result<ControlFlow<()>> {
+) -> Result<ControlFlow<FinishStatus>> {
     use cartesi_machine::break_reason;
 
-    let break_reason = cartesi_machine.run(1 << 40)?;
+    let break_reason = cartesi_machine.run(mcycle_end)?;
+    let mcycle = cartesi_machine.read_mcycle()?;
 
     let control_flow = match break_reason {
-        break_reason::FAILED => panic!("run failed"),
+        break_reason::FAILED => return Err(Error::Failed { mcycle }),
 
-        break_reason::HALTED => {
-            // TODO should it revert?
-            panic!("run halted: {}", cartesi_machine.read_mcycle()?)
-        }
+        break_reason::HALTED => return Err(Error::Halted { mcycle }),
 
-        // TODO Implement increment and timeout
-        break_reason::REACHED_TARGET_MCYCLE => todo!(),
+        break_reason::REACHED_TARGET_MCYCLE => {
+            return Err(Error::McycleLimit {
+                limit: mcycle_limit.unwrap_or(u64::MAX),
+                mcycle,
+            })
+        }
 
         break_reason::YIELDED_MANUALLY => {
-            handle_manual_yield(cartesi_machine)?;
-            ControlFlow::Break(())
+            ControlFlow::Break(handle_manual_yield(cartesi_machine, mcycle)?)
         }
 
         break_reason::YIELDED_AUTOMATICALLY => {
-            handle_automatic_yield(cartesi_machine, outputs, reports)?;
+            handle_automatic_yield(cartesi_machine, mcycle, outputs, reports)?;
             ControlFlow::Continue(())
         }
 
-        // TODO
-        break_reason::YIELDED_SOFTLY => todo!(),
+        // soft yields only give the host a chance to look at the machine, which resumes as is
+        break_reason::YIELDED_SOFTLY => ControlFlow::Continue(()),
 
-        i => unreachable!("cartesi machine impossible break reason: {}", i),
+        reason => return Err(Error::UnknownBreakReason { reason, mcycle }),
     };
 
     Ok(control_flow)
 }
 
-fn handle_manual_yield(cartesi_machine: &mut cartesi_machine::machine::Machine) -> Result<()> {
+fn handle_manual_yield(
+    cartesi_machine: &mut cartesi_machine::machine::Machine,
+    mcycle: u64,
+) -> Result<FinishStatus> {
     use cartesi_machine::htif;
 
-    let (_, reason, _) = get_yield(cartesi_machine)?;
+    let (_, reason, length) = get_yield(cartesi_machine)?;
 
-    match reason {
-        htif::tohost::manual::RX_ACCEPTED => (),
+    let status = match reason {
+        htif::tohost::manual::RX_ACCEPTED => FinishStatus::Accepted,
 
-        htif::tohost::manual::TX_EXCEPTION => {
-            unimplemented!("TX_EXCEPTION not implemented")
-        }
+        htif::tohost::manual::TX_EXCEPTION => FinishStatus::Exception(
+            cartesi_machine.read_memory(cartesi_machine::pma::CMIO_TX_BUFFER_START, length)?,
+        ),
 
-        htif::tohost::manual::RX_REJECTED => {
-            unimplemented!("RX_REJECTED not implemented")
-        }
+        htif::tohost::manual::RX_REJECTED => FinishStatus::Rejected,
 
-        i => unreachable!("cartesi machine impossible manual reason: {}", i),
-    }
+        reason => {
+            return Err(Error::UnknownManualYield {
+                reason,
+                length,
+                mcycle,
+            })
+        }
+    };
 
-    Ok(())
+    Ok(status)
 }
 
 fn handle_automatic_yield(
     cartesi_machine: &mut cartesi_machine::machine::Machine,
+    mcycle: u64,
     outputs: &mut OutputsForInput,
     reports: &mut Vec<Report>,
 ) -> Result<()> {
@@ -189,10 +345,16 @@ fn handle_automatic_yield(
         }
 
         htif::tohost::automatic::TX_REPORT => {
-            reports.push(data);
+            reports.push(Report { payload: data });
         }
 
-        i => unreachable!("cartesi machine impossible automatic reason: {}", i),
+        reason => {
+            return Err(Error::UnknownAutomaticYield {
+                reason,
+                length,
+                mcycle,
+            })
+        }
     }
 
     Ok(())
@@ -7,6 +7,7 @@ use types::*;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Output {
     Voucher(Voucher),
+    DelegateCallVoucher(DelegateCallVoucher),
     Notice(Notice),
 }
 
@@ -16,6 +17,11 @@ impl Output {
         let selector = &payload[..4];
         if selector == Notice::SELECTOR {
             Output::Notice(Notice::abi_decode(payload, true).expect("failed to decode notice"))
+        } else if selector == DelegateCallVoucher::SELECTOR {
+            Output::DelegateCallVoucher(
+                DelegateCallVoucher::abi_decode(payload, true)
+                    .expect("failed to decode delegate call voucher"),
+            )
         } else {
             assert_eq!(selector, Voucher::SELECTOR);
             Output::Voucher(Voucher::abi_decode(payload, true).expect("failed to decode voucher"))
@@ -25,25 +31,90 @@ impl Output {
     pub fn try_notice(&self) -> Option<&Notice> {
         match self {
             Self::Notice(n) => Some(n),
-            Self::Voucher(_) => None,
+            _ => None,
         }
     }
 
     pub fn expect_notice(&self) -> &Notice {
         self.try_notice()
-            .expect(format!("expected voucher {:?} to be a notice", self).as_str())
+            .unwrap_or_else(|| panic!("expected output {:?} to be a notice", self))
     }
 
     pub fn try_voucher(&self) -> Option<&Voucher> {
         match self {
-            Self::Notice(_) => None,
             Self::Voucher(v) => Some(v),
+            _ => None,
         }
     }
 
     pub fn expect_voucher(&self) -> &Voucher {
         self.try_voucher()
-            .expect(format!("expected notice {:?} to be a voucher", self).as_str())
+            .unwrap_or_else(|| panic!("expected output {:?} to be a voucher", self))
+    }
+
+    pub fn try_delegate_call_voucher(&self) -> Option<&DelegateCallVoucher> {
+        match self {
+            Self::DelegateCallVoucher(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn expect_delegate_call_voucher(&self) -> &DelegateCallVoucher {
+        self.try_delegate_call_voucher()
+            .unwrap_or_else(|| panic!("expected output {:?} to be a delegate call voucher", self))
+    }
+}
+
+/// How the dapp finished an advance or inspect request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FinishStatus {
+    Accepted,
+    Rejected,
+    /// The payload of the exception, after which the dapp takes no more requests.
+    Exception(Vec<u8>),
+}
+
+/// What became of an input sent with [`crate::Machine::advance_state`].
+#[derive(Clone, Debug)]
+pub struct AdvanceResult {
+    pub status: FinishStatus,
+    /// Empty unless the input was accepted.
+    pub outputs: OutputsForInput,
+    pub reports: Vec<Report>,
+    pub input_index: usize,
+    pub mcycles: u64,
+    /// The machine root hash once the input was processed, or reverted.
+    pub root_hash: [u8; 32],
+}
+
+impl AdvanceResult {
+    pub fn is_accepted(&self) -> bool {
+        self.status == FinishStatus::Accepted
+    }
+}
+
+/// What became of a request sent with [`crate::Machine::inspect`].
+#[derive(Clone, Debug)]
+pub struct InspectResult {
+    /// Rejecting or raising an exception only ends the inspect, the machine is left as it was.
+    pub status: FinishStatus,
+    pub reports: Vec<Report>,
+}
+
+impl InspectResult {
+    pub fn is_accepted(&self) -> bool {
+        self.status == FinishStatus::Accepted
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    pub payload: Vec<u8>,
+}
+
+impl Report {
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.payload).ok()
     }
 }
 
@@ -80,6 +151,13 @@ impl OutputsForInput {
     pub fn vouchers(&self) -> Vec<&Voucher> {
         self.list.iter().filter_map(|x| x.try_voucher()).collect()
     }
+
+    pub fn delegate_call_vouchers(&self) -> Vec<&DelegateCallVoucher> {
+        self.list
+            .iter()
+            .filter_map(|x| x.try_delegate_call_voucher())
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug)]
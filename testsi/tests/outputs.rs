@@ -0,0 +1,79 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::SolCall;
+use testsi::{Output, OutputsForInput};
+use types::{DelegateCallVoucher, Notice, Voucher};
+
+fn outputs() -> (Voucher, DelegateCallVoucher, Notice) {
+    (
+        Voucher {
+            destination: Address::repeat_byte(0xaa),
+            value: U256::from(1),
+            payload: Bytes::from_static(b"call"),
+        },
+        DelegateCallVoucher {
+            destination: Address::repeat_byte(0xbb),
+            payload: Bytes::from_static(b"delegate"),
+        },
+        Notice {
+            payload: Bytes::from_static(b"hello"),
+        },
+    )
+}
+
+#[test]
+fn outputs_are_decoded_by_selector() {
+    let (voucher, delegate_call_voucher, notice) = outputs();
+
+    assert_eq!(
+        Output::abi_decode(&voucher.abi_encode()),
+        Output::Voucher(voucher)
+    );
+    assert_eq!(
+        Output::abi_decode(&delegate_call_voucher.abi_encode()),
+        Output::DelegateCallVoucher(delegate_call_voucher)
+    );
+    assert_eq!(
+        Output::abi_decode(&notice.abi_encode()),
+        Output::Notice(notice)
+    );
+}
+
+#[test]
+fn outputs_are_told_apart() {
+    let (voucher, delegate_call_voucher, notice) = outputs();
+    let output = Output::DelegateCallVoucher(delegate_call_voucher.clone());
+
+    assert_eq!(
+        output.try_delegate_call_voucher(),
+        Some(&delegate_call_voucher)
+    );
+    assert_eq!(
+        output.expect_delegate_call_voucher(),
+        &delegate_call_voucher
+    );
+    assert_eq!(output.try_voucher(), None);
+    assert_eq!(output.try_notice(), None);
+    assert_eq!(Output::Voucher(voucher).try_delegate_call_voucher(), None);
+    assert_eq!(Output::Notice(notice).try_delegate_call_voucher(), None);
+}
+
+#[test]
+#[should_panic(expected = "to be a delegate call voucher")]
+fn expecting_the_wrong_output_panics() {
+    let (voucher, _, _) = outputs();
+    Output::Voucher(voucher).expect_delegate_call_voucher();
+}
+
+#[test]
+fn outputs_for_input_are_filtered_by_kind() {
+    let (voucher, delegate_call_voucher, notice) = outputs();
+    let mut list = OutputsForInput::default();
+    list.push_encoded(&voucher.abi_encode());
+    list.push_encoded(&delegate_call_voucher.abi_encode());
+    list.push_encoded(&notice.abi_encode());
+
+    assert_eq!(list.delegate_call_vouchers(), vec![&delegate_call_voucher]);
+    assert_eq!(list.vouchers(), vec![&voucher]);
+    assert_eq!(list.notices(), vec![&notice]);
+    assert_eq!(list[1], Output::DelegateCallVoucher(delegate_call_voucher));
+}
@@ -0,0 +1,69 @@
+use crate::{Finish, Input, Request, Result, Rollup, RollupError};
+
+pub trait App {
+    type Error: std::fmt::Display;
+
+    fn advance<R: Rollup>(
+        &mut self,
+        rollup: &mut R,
+        input: Input,
+    ) -> std::result::Result<Finish, Self::Error>;
+
+    fn inspect<R: Rollup>(
+        &self,
+        rollup: &mut R,
+        payload: &[u8],
+    ) -> std::result::Result<Finish, Self::Error> {
+        let _ = (rollup, payload);
+        Ok(Finish::Accept)
+    }
+}
+
+/// Feeds every request to `app`. A handler error is emitted as a report and rejects the request;
+/// only errors from the rollup itself end the loop. Returns once the rollup runs out of requests,
+/// which only happens with a mock.
+pub fn run<A: App, R: Rollup>(mut app: A, mut rollup: R) -> Result<()> {
+    let mut finish = Finish::Accept;
+
+    loop {
+        let request = match rollup.next_request(finish) {
+            Ok(request) => request,
+            Err(RollupError::NoMoreRequests) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        let handled = match request {
+            Request::Advance(input) => app.advance(&mut rollup, input),
+            Request::Inspect(payload) => app.inspect(&mut rollup, &payload),
+        };
+
+        finish = match handled {
+            Ok(finish) => finish,
+            Err(e) => {
+                rollup.emit_report(e.to_string().as_bytes())?;
+                Finish::Reject
+            }
+        };
+    }
+}
+
+// Lets `run` borrow the app, so its state can be looked at once the requests run out.
+impl<A: App + ?Sized> App for &mut A {
+    type Error = A::Error;
+
+    fn advance<R: Rollup>(
+        &mut self,
+        rollup: &mut R,
+        input: Input,
+    ) -> std::result::Result<Finish, Self::Error> {
+        (**self).advance(rollup, input)
+    }
+
+    fn inspect<R: Rollup>(
+        &self,
+        rollup: &mut R,
+        payload: &[u8],
+    ) -> std::result::Result<Finish, Self::Error> {
+        (**self).inspect(rollup, payload)
+    }
+}
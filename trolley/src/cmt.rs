@@ -1,121 +1,289 @@
-use alloy_primitives::U256;
+use crate::{types, Result, RollupError};
+use alloy_primitives::{Address, U256};
+
+const ADVANCE: i32 = libcmt_sys::HTIF_YIELD_REASON_ADVANCE as i32;
+const INSPECT: i32 = libcmt_sys::HTIF_YIELD_REASON_INSPECT as i32;
 
 pub struct RollupCmt {
     r: libcmt_sys::cmt_rollup_t,
 }
 
+/// An advance input borrowed from the rx buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputRef<'a> {
+    pub chain_id: u64,
+    pub app_contract: Address,
+    pub msg_sender: Address,
+    pub block_number: u64,
+    pub block_timestamp: u64,
+    pub prev_randao: U256,
+    pub index: u64,
+    pub payload: &'a [u8],
+}
+
+impl InputRef<'_> {
+    pub fn to_owned(&self) -> types::Input {
+        types::Input {
+            chainId: U256::from(self.chain_id),
+            appContract: self.app_contract,
+            msgSender: self.msg_sender,
+            blockNumber: U256::from(self.block_number),
+            blockTimestamp: U256::from(self.block_timestamp),
+            prevRandao: self.prev_randao,
+            index: U256::from(self.index),
+            payload: self.payload.to_vec().into(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RequestRef<'a> {
+    Advance(InputRef<'a>),
+    Inspect(&'a [u8]),
+}
+
+impl RequestRef<'_> {
+    pub fn to_owned(&self) -> types::Request {
+        match self {
+            Self::Advance(input) => types::Request::Advance(input.to_owned()),
+            Self::Inspect(payload) => types::Request::Inspect(payload.to_vec()),
+        }
+    }
+}
+
+/// Emits outputs for the request returned alongside it by [`RollupCmt::next_request_ref`].
+/// Outputs go through the tx buffer, so the borrowed request stays valid while emitting.
+pub struct Emitter<'a> {
+    r: &'a mut libcmt_sys::cmt_rollup_t,
+}
+
 impl RollupCmt {
-    pub fn new() -> Self {
+    pub fn try_new() -> Result<Self> {
         use std::mem::MaybeUninit;
 
         let r = {
             let mut r: MaybeUninit<libcmt_sys::cmt_rollup_t> = MaybeUninit::uninit();
-            let err = unsafe { libcmt_sys::cmt_rollup_init(r.as_mut_ptr()) };
-            assert!(err == 0, "failed to instantiate rollup: {}", err);
+            check(unsafe { libcmt_sys::cmt_rollup_init(r.as_mut_ptr()) })?;
             unsafe { r.assume_init() }
         };
 
-        Self { r }
+        Ok(Self { r })
     }
-}
-
-impl crate::Rollup for RollupCmt {
-    fn next_input(&mut self) -> types::Input {
-        use std::mem::MaybeUninit;
 
-        let mut finish = libcmt_sys::cmt_rollup_finish {
-            accept_previous_request: true,
+    /// Like [`crate::Rollup::next_request`], but borrows the request from the rx buffer instead
+    /// of copying it. The borrow ends before the request can be finished.
+    pub fn next_request_ref<'a>(
+        &'a mut self,
+        finish: types::Finish,
+    ) -> Result<(RequestRef<'a>, Emitter<'a>)> {
+        let mut cmt_finish = libcmt_sys::cmt_rollup_finish {
+            accept_previous_request: finish == types::Finish::Accept,
             next_request_type: 0,
             next_request_payload_length: 0,
         };
 
-        unsafe {
-            assert!(libcmt_sys::cmt_rollup_finish(&mut self.r, &mut finish) == 0);
-        }
+        check(unsafe { libcmt_sys::cmt_rollup_finish(&mut self.r, &mut cmt_finish) })?;
+
+        let request = match cmt_finish.next_request_type {
+            ADVANCE => RequestRef::Advance(self.read_advance_state()?),
+            INSPECT => RequestRef::Inspect(self.read_inspect_state()?),
+            i => return Err(RollupError::UnknownRequestType(i)),
+        };
+        // SAFETY: the request points into the rx buffer rather than into `self`, and emitting
+        // only writes the tx buffer, so it stays valid alongside the emitter until `self` is
+        // borrowed again to finish it.
+        let request = unsafe { std::mem::transmute::<RequestRef<'_>, RequestRef<'a>>(request) };
+
+        Ok((request, Emitter { r: &mut self.r }))
+    }
 
-        // TODO: add inspect
-        assert!(finish.next_request_type == libcmt_sys::HTIF_YIELD_REASON_ADVANCE as i32);
+    fn read_advance_state(&mut self) -> Result<InputRef<'_>> {
+        use std::mem::MaybeUninit;
 
         let advance = unsafe {
             let mut advance: MaybeUninit<libcmt_sys::cmt_rollup_advance_t> = MaybeUninit::uninit();
-            assert!(
-                libcmt_sys::cmt_rollup_read_advance_state(&mut self.r, advance.as_mut_ptr()) == 0
-            );
+            check(libcmt_sys::cmt_rollup_read_advance_state(
+                &mut self.r,
+                advance.as_mut_ptr(),
+            ))?;
             advance.assume_init()
         };
 
-        let payload_length = advance.payload.length as usize;
-        let mut payload = vec![0; payload_length];
-        payload.copy_from_slice(unsafe {
-            std::slice::from_raw_parts(advance.payload.data as *const u8, payload_length)
-        });
+        Ok(InputRef {
+            chain_id: advance.chain_id,
+            app_contract: advance.app_contract.data.into(),
+            msg_sender: advance.msg_sender.data.into(),
+            block_number: advance.block_number,
+            block_timestamp: advance.block_timestamp,
+            prev_randao: U256::from_be_bytes(advance.prev_randao.data),
+            index: advance.index,
+            payload: unsafe { borrow_bytes(&advance.payload) },
+        })
+    }
 
-        types::Input {
-            chainId: U256::from(advance.chain_id),
-            appContract: advance.app_contract.data.into(),
-            msgSender: advance.msg_sender.data.into(),
-            blockNumber: U256::from(advance.block_number),
-            blockTimestamp: U256::from(advance.block_timestamp),
-            prevRandao: U256::from_be_bytes(advance.prev_randao.data),
-            index: U256::from(advance.index),
-            payload: payload.into(),
-        }
+    fn read_inspect_state(&mut self) -> Result<&[u8]> {
+        use std::mem::MaybeUninit;
+
+        let inspect = unsafe {
+            let mut inspect: MaybeUninit<libcmt_sys::cmt_rollup_inspect_t> = MaybeUninit::uninit();
+            check(libcmt_sys::cmt_rollup_read_inspect_state(
+                &mut self.r,
+                inspect.as_mut_ptr(),
+            ))?;
+            inspect.assume_init()
+        };
+
+        Ok(unsafe { borrow_bytes(&inspect.payload) })
+    }
+
+    fn emitter(&mut self) -> Emitter<'_> {
+        Emitter { r: &mut self.r }
     }
+}
 
-    fn emit_voucher(&mut self, voucher: &types::Voucher) {
+impl Emitter<'_> {
+    pub fn emit_voucher(&mut self, voucher: &types::Voucher) -> Result<u64> {
         let destination = voucher.destination;
         let value = voucher.value.to_be_bytes();
         let mut index = 0;
 
-        unsafe {
-            assert!(
-                libcmt_sys::cmt_rollup_emit_voucher(
-                    &mut self.r,
-                    &libcmt_sys::cmt_abi_address {
-                        data: **destination
-                    },
-                    &libcmt_sys::cmt_abi_u256 { data: value },
-                    &libcmt_sys::cmt_abi_bytes_t {
-                        data: voucher.payload.as_ptr() as *mut std::ffi::c_void,
-                        length: voucher.payload.len(),
-                    },
-                    &mut index
-                ) == 0,
-                "failed emitting voucher"
+        check(unsafe {
+            libcmt_sys::cmt_rollup_emit_voucher(
+                self.r,
+                &libcmt_sys::cmt_abi_address {
+                    data: **destination,
+                },
+                &libcmt_sys::cmt_abi_u256 { data: value },
+                &libcmt_sys::cmt_abi_bytes_t {
+                    data: voucher.payload.as_ptr() as *mut std::ffi::c_void,
+                    length: voucher.payload.len(),
+                },
+                &mut index,
             )
-        }
+        })?;
+
+        Ok(index)
+    }
+
+    pub fn emit_delegate_call_voucher(
+        &mut self,
+        voucher: &types::DelegateCallVoucher,
+    ) -> Result<u64> {
+        let destination = voucher.destination;
+        let mut index = 0;
+
+        check(unsafe {
+            libcmt_sys::cmt_rollup_emit_delegate_call_voucher(
+                self.r,
+                &libcmt_sys::cmt_abi_address {
+                    data: **destination,
+                },
+                &libcmt_sys::cmt_abi_bytes_t {
+                    data: voucher.payload.as_ptr() as *mut std::ffi::c_void,
+                    length: voucher.payload.len(),
+                },
+                &mut index,
+            )
+        })?;
+
+        Ok(index)
     }
 
-    fn emit_notice(&mut self, notice: &types::Notice) {
+    pub fn emit_notice(&mut self, notice: &types::Notice) -> Result<u64> {
         let mut index = 0;
-        unsafe {
-            assert!(
-                libcmt_sys::cmt_rollup_emit_notice(
-                    &mut self.r,
-                    &libcmt_sys::cmt_abi_bytes_t {
-                        data: notice.payload.as_ptr() as *mut std::ffi::c_void,
-                        length: notice.payload.len()
-                    },
-                    &mut index
-                ) == 0,
-                "failed emitting notice"
+
+        check(unsafe {
+            libcmt_sys::cmt_rollup_emit_notice(
+                self.r,
+                &libcmt_sys::cmt_abi_bytes_t {
+                    data: notice.payload.as_ptr() as *mut std::ffi::c_void,
+                    length: notice.payload.len(),
+                },
+                &mut index,
             )
-        }
+        })?;
+
+        Ok(index)
     }
 
-    fn emit_report(&mut self, report: &[u8]) {
-        unsafe {
-            assert!(
-                libcmt_sys::cmt_rollup_emit_report(
-                    &mut self.r,
-                    &libcmt_sys::cmt_abi_bytes_t {
-                        data: report.as_ptr() as *mut std::ffi::c_void,
-                        length: report.len(),
-                    }
-                ) == 0,
-                "failed emitting report"
+    pub fn emit_report(&mut self, report: &[u8]) -> Result<()> {
+        check(unsafe {
+            libcmt_sys::cmt_rollup_emit_report(
+                self.r,
+                &libcmt_sys::cmt_abi_bytes_t {
+                    data: report.as_ptr() as *mut std::ffi::c_void,
+                    length: report.len(),
+                },
             )
-        }
+        })
+    }
+
+    pub fn emit_exception(&mut self, payload: &[u8]) -> Result<()> {
+        check(unsafe {
+            libcmt_sys::cmt_rollup_emit_exception(
+                self.r,
+                &libcmt_sys::cmt_abi_bytes_t {
+                    data: payload.as_ptr() as *mut std::ffi::c_void,
+                    length: payload.len(),
+                },
+            )
+        })
+    }
+}
+
+impl crate::Rollup for RollupCmt {
+    fn next_request(&mut self, finish: types::Finish) -> Result<types::Request> {
+        let (request, _) = self.next_request_ref(finish)?;
+        Ok(request.to_owned())
+    }
+
+    fn emit_voucher(&mut self, voucher: &types::Voucher) -> Result<u64> {
+        self.emitter().emit_voucher(voucher)
+    }
+
+    fn emit_delegate_call_voucher(&mut self, voucher: &types::DelegateCallVoucher) -> Result<u64> {
+        self.emitter().emit_delegate_call_voucher(voucher)
+    }
+
+    fn emit_notice(&mut self, notice: &types::Notice) -> Result<u64> {
+        self.emitter().emit_notice(notice)
+    }
+
+    fn emit_report(&mut self, report: &[u8]) -> Result<()> {
+        self.emitter().emit_report(report)
+    }
+
+    fn emit_exception(&mut self, payload: &[u8]) -> Result<()> {
+        self.emitter().emit_exception(payload)
+    }
+
+    // The response overwrites the rx buffer, which is why this is not available on `Emitter`.
+    fn gio_request(&mut self, domain: u16, id: &[u8]) -> Result<types::GioResponse> {
+        let mut gio = libcmt_sys::cmt_gio_t {
+            domain,
+            id_length: id
+                .len()
+                .try_into()
+                .map_err(|_| RollupError::BufferTooSmall)?,
+            id: id.as_ptr() as *mut std::ffi::c_void,
+            response_code: 0,
+            response_data_length: 0,
+            response_data: std::ptr::null_mut(),
+        };
+
+        check(unsafe { libcmt_sys::cmt_gio_request(&mut self.r, &mut gio) })?;
+
+        let data = unsafe {
+            borrow_bytes(&libcmt_sys::cmt_abi_bytes_t {
+                length: gio.response_data_length as usize,
+                data: gio.response_data,
+            })
+        };
+
+        Ok(types::GioResponse {
+            code: gio.response_code,
+            data: data.to_vec(),
+        })
     }
 }
 
@@ -124,3 +292,20 @@ impl Drop for RollupCmt {
         unsafe { libcmt_sys::cmt_rollup_fini(&mut self.r) }
     }
 }
+
+pub(crate) fn check(err: std::ffi::c_int) -> Result<()> {
+    if err == 0 {
+        Ok(())
+    } else {
+        Err(RollupError::from_errno(err))
+    }
+}
+
+// The caller picks the lifetime, which must not outlive the buffer `bytes` points into.
+unsafe fn borrow_bytes<'a>(bytes: &libcmt_sys::cmt_abi_bytes_t) -> &'a [u8] {
+    if bytes.length == 0 {
+        return &[];
+    }
+
+    std::slice::from_raw_parts(bytes.data as *const u8, bytes.length)
+}
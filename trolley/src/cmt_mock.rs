@@ -0,0 +1,98 @@
+use crate::Input;
+use alloy_sol_types::SolCall;
+use std::{io, path::PathBuf};
+
+const ADVANCE: i32 = libcmt_sys::HTIF_YIELD_REASON_ADVANCE as i32;
+const INSPECT: i32 = libcmt_sys::HTIF_YIELD_REASON_INSPECT as i32;
+
+/// Stages request files for the libcmt host mock and reads back the files it writes.
+///
+/// The mock takes its requests from `CMT_INPUTS`, a comma separated list of `<type>:<path>`
+/// entries, and writes the outputs, reports and exceptions of `<name>.bin` next to it as
+/// `<name>.output-<n>.bin`, `<name>.report-<n>.bin` and `<name>.exception-<n>.bin`.
+#[derive(Clone, Debug)]
+pub struct MockIo {
+    dir: PathBuf,
+    requests: Vec<(i32, PathBuf)>,
+}
+
+impl MockIo {
+    pub fn new<T: Into<PathBuf>>(dir: T) -> io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            dir,
+            requests: Vec::new(),
+        })
+    }
+
+    pub fn stage_advance(&mut self, input: &Input) -> io::Result<usize> {
+        self.stage(ADVANCE, &input.abi_encode())
+    }
+
+    pub fn stage_inspect<T: AsRef<[u8]>>(&mut self, payload: &T) -> io::Result<usize> {
+        self.stage(INSPECT, payload.as_ref())
+    }
+
+    pub fn cmt_inputs(&self) -> String {
+        self.requests
+            .iter()
+            .map(|(kind, path)| format!("{}:{}", kind, path.display()))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Points the mock at the staged requests; must be called before `RollupCmt::try_new`.
+    ///
+    /// To avoid touching the environment at all, pass [`MockIo::cmt_inputs`] as `CMT_INPUTS` to
+    /// a child process instead.
+    ///
+    /// # Safety
+    ///
+    /// This sets `CMT_INPUTS` with `std::env::set_var`, which races with any other thread reading
+    /// or writing the environment, libc `getenv` included. Only call it while no other thread is
+    /// running, e.g. from a test binary with a single test.
+    pub unsafe fn install(&self) {
+        std::env::set_var("CMT_INPUTS", self.cmt_inputs());
+    }
+
+    pub fn outputs(&self, request: usize) -> io::Result<Vec<Vec<u8>>> {
+        self.collect(request, "output")
+    }
+
+    pub fn reports(&self, request: usize) -> io::Result<Vec<Vec<u8>>> {
+        self.collect(request, "report")
+    }
+
+    pub fn exception(&self, request: usize) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.collect(request, "exception")?.pop())
+    }
+
+    fn stage(&mut self, kind: i32, data: &[u8]) -> io::Result<usize> {
+        let index = self.requests.len();
+        let path = self.dir.join(format!("request-{}.bin", index));
+        std::fs::write(&path, data)?;
+
+        self.requests.push((kind, path));
+        Ok(index)
+    }
+
+    fn collect(&self, request: usize, kind: &str) -> io::Result<Vec<Vec<u8>>> {
+        let mut files = Vec::new();
+
+        loop {
+            let path = self.output_path(request, kind, files.len());
+            match std::fs::read(&path) {
+                Ok(data) => files.push(data),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(files),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn output_path(&self, request: usize, kind: &str, n: usize) -> PathBuf {
+        self.dir
+            .join(format!("request-{}.{}-{}.bin", request, kind, n))
+    }
+}
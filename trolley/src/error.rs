@@ -0,0 +1,48 @@
+// Linux errno values, returned negated by libcmt.
+const EINVAL: i32 = 22;
+const EDOM: i32 = 33;
+const ENOSYS: i32 = 38;
+const EBADMSG: i32 = 74;
+const ENOBUFS: i32 = 105;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum RollupError {
+    #[error("buffer too small")]
+    BufferTooSmall,
+
+    #[error("invalid argument")]
+    InvalidArgument,
+
+    #[error("invalid request")]
+    InvalidRequest,
+
+    #[error("value out of range")]
+    OutOfRange,
+
+    #[error("not supported by the linked libcmt")]
+    Unsupported,
+
+    #[error("no more requests")]
+    NoMoreRequests,
+
+    #[error("unknown request type: {0}")]
+    UnknownRequestType(i32),
+
+    #[error("libcmt error: {0}")]
+    Errno(i32),
+}
+
+pub type Result<T> = std::result::Result<T, RollupError>;
+
+impl RollupError {
+    pub fn from_errno(err: i32) -> Self {
+        match -err {
+            ENOBUFS => Self::BufferTooSmall,
+            EINVAL => Self::InvalidArgument,
+            EBADMSG => Self::InvalidRequest,
+            EDOM => Self::OutOfRange,
+            ENOSYS => Self::Unsupported,
+            _ => Self::Errno(err),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+/// Incremental keccak-256, as used by libcmt for output hashes and function selectors.
+#[derive(Clone, Copy)]
+pub struct Keccak {
+    state: libcmt_sys::cmt_keccak_t,
+}
+
+impl Keccak {
+    pub fn new() -> Self {
+        use std::mem::MaybeUninit;
+
+        let state = unsafe {
+            let mut state: MaybeUninit<libcmt_sys::cmt_keccak_t> = MaybeUninit::uninit();
+            libcmt_sys::cmt_keccak_init(state.as_mut_ptr());
+            state.assume_init()
+        };
+
+        Self { state }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        unsafe {
+            libcmt_sys::cmt_keccak_update(
+                &mut self.state,
+                data.len(),
+                data.as_ptr() as *const std::ffi::c_void,
+            )
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; 32] {
+        let mut md = [0; 32];
+        unsafe {
+            libcmt_sys::cmt_keccak_final(&mut self.state, md.as_mut_ptr() as *mut std::ffi::c_void)
+        };
+        md
+    }
+}
+
+impl Default for Keccak {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut md = [0; 32];
+    unsafe {
+        libcmt_sys::cmt_keccak_data(
+            data.len(),
+            data.as_ptr() as *const std::ffi::c_void,
+            md.as_mut_ptr(),
+        )
+    };
+    md
+}
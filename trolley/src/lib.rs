@@ -1,12 +1,65 @@
 #[cfg(feature = "cmt")]
 pub mod cmt;
+#[cfg(feature = "cmt-mock")]
+pub mod cmt_mock;
+#[cfg(feature = "cmt")]
+pub mod keccak;
+#[cfg(feature = "cmt")]
+pub mod merkle;
+#[cfg(feature = "cmt")]
+pub use libcmt_sys::LIBCMT_VERSION;
+
+pub mod mock;
+pub mod panic;
+pub mod portals;
+pub mod router;
+pub mod wallet;
+
+mod app;
+pub use app::*;
+
+mod error;
+pub use error::*;
 
 mod types;
-pub use types::*;
+pub use crate::types::*;
 
 pub trait Rollup {
-    fn next_input(&mut self) -> types::Input;
-    fn emit_voucher(&mut self, voucher: &types::Voucher);
-    fn emit_notice(&mut self, notice: &types::Notice);
-    fn emit_report(&mut self, report: &[u8]);
+    fn next_request(&mut self, finish: types::Finish) -> Result<types::Request>;
+    fn emit_voucher(&mut self, voucher: &types::Voucher) -> Result<u64>;
+    fn emit_delegate_call_voucher(&mut self, voucher: &types::DelegateCallVoucher) -> Result<u64>;
+    fn emit_notice(&mut self, notice: &types::Notice) -> Result<u64>;
+    fn emit_report(&mut self, report: &[u8]) -> Result<()>;
+    fn emit_exception(&mut self, payload: &[u8]) -> Result<()>;
+    fn gio_request(&mut self, domain: u16, id: &[u8]) -> Result<types::GioResponse>;
+}
+
+impl<R: Rollup + ?Sized> Rollup for &mut R {
+    fn next_request(&mut self, finish: types::Finish) -> Result<types::Request> {
+        (**self).next_request(finish)
+    }
+
+    fn emit_voucher(&mut self, voucher: &types::Voucher) -> Result<u64> {
+        (**self).emit_voucher(voucher)
+    }
+
+    fn emit_delegate_call_voucher(&mut self, voucher: &types::DelegateCallVoucher) -> Result<u64> {
+        (**self).emit_delegate_call_voucher(voucher)
+    }
+
+    fn emit_notice(&mut self, notice: &types::Notice) -> Result<u64> {
+        (**self).emit_notice(notice)
+    }
+
+    fn emit_report(&mut self, report: &[u8]) -> Result<()> {
+        (**self).emit_report(report)
+    }
+
+    fn emit_exception(&mut self, payload: &[u8]) -> Result<()> {
+        (**self).emit_exception(payload)
+    }
+
+    fn gio_request(&mut self, domain: u16, id: &[u8]) -> Result<types::GioResponse> {
+        (**self).gio_request(domain, id)
+    }
 }
@@ -0,0 +1,84 @@
+use crate::{cmt::check, types, Result};
+use alloy_sol_types::SolCall;
+
+/// The outputs merkle tree, built the way libcmt builds it while emitting: each voucher or
+/// notice is a leaf holding the keccak of its ABI encoding.
+#[derive(Clone)]
+pub struct OutputsMerkle {
+    m: libcmt_sys::cmt_merkle_t,
+}
+
+impl OutputsMerkle {
+    pub fn new() -> Self {
+        use std::mem::MaybeUninit;
+
+        let m = unsafe {
+            let mut m: MaybeUninit<libcmt_sys::cmt_merkle_t> = MaybeUninit::uninit();
+            libcmt_sys::cmt_merkle_init(m.as_mut_ptr());
+            m.assume_init()
+        };
+
+        Self { m }
+    }
+
+    // The getters take a mutable pointer, although libcmt only reads through it.
+    fn as_mut_ptr(&self) -> *mut libcmt_sys::cmt_merkle_t {
+        &self.m as *const _ as *mut _
+    }
+
+    pub fn leaf_count(&self) -> u64 {
+        unsafe { libcmt_sys::cmt_merkle_get_leaf_count(self.as_mut_ptr()) }
+    }
+
+    pub fn root_hash(&self) -> [u8; 32] {
+        let mut root = [0; 32];
+        unsafe { libcmt_sys::cmt_merkle_get_root_hash(self.as_mut_ptr(), root.as_mut_ptr()) };
+        root
+    }
+
+    pub fn reset(&mut self) {
+        unsafe { libcmt_sys::cmt_merkle_reset(&mut self.m) }
+    }
+
+    pub fn push_hash(&mut self, hash: &[u8; 32]) -> Result<()> {
+        check(unsafe { libcmt_sys::cmt_merkle_push_back(&mut self.m, hash.as_ptr()) })
+    }
+
+    /// Pushes the keccak of `data`.
+    pub fn push_data(&mut self, data: &[u8]) -> Result<()> {
+        check(unsafe {
+            libcmt_sys::cmt_merkle_push_back_data(
+                &mut self.m,
+                data.len(),
+                data.as_ptr() as *const std::ffi::c_void,
+            )
+        })
+    }
+
+    pub fn push_voucher(&mut self, voucher: &types::Voucher) -> Result<()> {
+        self.push_data(&voucher.abi_encode())
+    }
+
+    pub fn push_delegate_call_voucher(
+        &mut self,
+        voucher: &types::DelegateCallVoucher,
+    ) -> Result<()> {
+        self.push_data(&voucher.abi_encode())
+    }
+
+    pub fn push_notice(&mut self, notice: &types::Notice) -> Result<()> {
+        self.push_data(&notice.abi_encode())
+    }
+}
+
+impl Default for OutputsMerkle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for OutputsMerkle {
+    fn drop(&mut self) {
+        unsafe { libcmt_sys::cmt_merkle_fini(&mut self.m) }
+    }
+}
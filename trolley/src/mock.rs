@@ -0,0 +1,245 @@
+use crate::{
+    DelegateCallVoucher, Finish, GioResponse, Input, Notice, Request, Result, RollupError, Voucher,
+};
+use alloy_primitives::{Address, U256};
+use std::collections::VecDeque;
+
+/// An input from `sender` carrying `payload`, on a local devnet's chain id with every other field
+/// zeroed, for pushing to a [`RollupMock`].
+pub fn input<T: AsRef<[u8]>>(sender: Address, payload: &T) -> Input {
+    Input {
+        chainId: U256::from(31337),
+        appContract: Address::ZERO,
+        msgSender: sender,
+        blockNumber: U256::ZERO,
+        blockTimestamp: U256::ZERO,
+        prevRandao: U256::ZERO,
+        index: U256::ZERO,
+        payload: payload.as_ref().to_vec().into(),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Output {
+    Voucher(Voucher),
+    DelegateCallVoucher(DelegateCallVoucher),
+    Notice(Notice),
+    Report(Vec<u8>),
+    Exception(Vec<u8>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Processed {
+    pub request: Request,
+    pub outputs: Vec<Output>,
+    pub finish: Option<Finish>,
+}
+
+type GioResponder = Box<dyn FnMut(u16, &[u8]) -> Result<GioResponse>>;
+
+#[derive(Default)]
+pub struct RollupMock {
+    pending: VecDeque<Request>,
+    processed: Vec<Processed>,
+    output_count: u64,
+    gio_responder: Option<GioResponder>,
+}
+
+impl std::fmt::Debug for RollupMock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RollupMock")
+            .field("pending", &self.pending)
+            .field("processed", &self.processed)
+            .field("output_count", &self.output_count)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RollupMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_inputs<T: IntoIterator<Item = Input>>(inputs: T) -> Self {
+        let mut mock = Self::new();
+        for input in inputs {
+            mock.push_advance(input);
+        }
+        mock
+    }
+
+    pub fn push_advance(&mut self, input: Input) {
+        self.pending.push_back(Request::Advance(input));
+    }
+
+    pub fn push_inspect<T: AsRef<[u8]>>(&mut self, payload: &T) {
+        self.pending
+            .push_back(Request::Inspect(payload.as_ref().into()));
+    }
+
+    /// Answers `gio_request`s, which fail with [`RollupError::InvalidRequest`] until one is set.
+    pub fn set_gio_responder<F>(&mut self, responder: F)
+    where
+        F: FnMut(u16, &[u8]) -> Result<GioResponse> + 'static,
+    {
+        self.gio_responder = Some(Box::new(responder));
+    }
+
+    /// Every request taken so far with everything it emitted, rejected or not.
+    pub fn processed(&self) -> &[Processed] {
+        &self.processed
+    }
+
+    /// The outputs of accepted advance requests, which are the ones the node would keep.
+    pub fn outputs(&self) -> impl Iterator<Item = &Output> {
+        self.processed
+            .iter()
+            .filter(|p| {
+                matches!(p.request, Request::Advance(_)) && p.finish == Some(Finish::Accept)
+            })
+            .flat_map(|p| p.outputs.iter())
+    }
+
+    pub fn vouchers(&self) -> Vec<&Voucher> {
+        self.outputs()
+            .filter_map(|o| match o {
+                Output::Voucher(v) => Some(v),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn delegate_call_vouchers(&self) -> Vec<&DelegateCallVoucher> {
+        self.outputs()
+            .filter_map(|o| match o {
+                Output::DelegateCallVoucher(v) => Some(v),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn notices(&self) -> Vec<&Notice> {
+        self.outputs()
+            .filter_map(|o| match o {
+                Output::Notice(n) => Some(n),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Reports of every request, as they are not reverted with a rejected request.
+    pub fn reports(&self) -> Vec<&[u8]> {
+        self.processed
+            .iter()
+            .flat_map(|p| p.outputs.iter())
+            .filter_map(|o| match o {
+                Output::Report(r) => Some(r.as_slice()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn exception(&self) -> Option<&[u8]> {
+        self.processed
+            .iter()
+            .flat_map(|p| p.outputs.iter())
+            .find_map(|o| match o {
+                Output::Exception(e) => Some(e.as_slice()),
+                _ => None,
+            })
+    }
+
+    fn current(&mut self) -> Result<&mut Processed> {
+        self.processed
+            .last_mut()
+            .filter(|p| p.finish.is_none())
+            .ok_or(RollupError::InvalidRequest)
+    }
+
+    fn push_output(&mut self, output: Output) -> Result<()> {
+        self.current()?.outputs.push(output);
+        Ok(())
+    }
+
+    // vouchers and notices only make sense for advance requests
+    fn push_indexed_output(&mut self, output: Output) -> Result<u64> {
+        let current = self.current()?;
+        if !matches!(current.request, Request::Advance(_)) {
+            return Err(RollupError::InvalidRequest);
+        }
+
+        current.outputs.push(output);
+        self.output_count += 1;
+        Ok(self.output_count - 1)
+    }
+}
+
+impl crate::Rollup for RollupMock {
+    fn next_request(&mut self, finish: Finish) -> Result<Request> {
+        if let Ok(current) = self.current() {
+            current.finish = Some(finish);
+
+            // a rejected request is reverted, along with the output indices it took
+            if finish == Finish::Reject {
+                let reverted = current
+                    .outputs
+                    .iter()
+                    .filter(|o| {
+                        matches!(
+                            o,
+                            Output::Voucher(_) | Output::DelegateCallVoucher(_) | Output::Notice(_)
+                        )
+                    })
+                    .count();
+                self.output_count -= reverted as u64;
+            }
+        }
+
+        // the machine does not leave the exception state
+        if self.exception().is_some() {
+            return Err(RollupError::NoMoreRequests);
+        }
+
+        let request = self
+            .pending
+            .pop_front()
+            .ok_or(RollupError::NoMoreRequests)?;
+        self.processed.push(Processed {
+            request: request.clone(),
+            outputs: Vec::new(),
+            finish: None,
+        });
+
+        Ok(request)
+    }
+
+    fn emit_voucher(&mut self, voucher: &Voucher) -> Result<u64> {
+        self.push_indexed_output(Output::Voucher(voucher.clone()))
+    }
+
+    fn emit_delegate_call_voucher(&mut self, voucher: &DelegateCallVoucher) -> Result<u64> {
+        self.push_indexed_output(Output::DelegateCallVoucher(voucher.clone()))
+    }
+
+    fn emit_notice(&mut self, notice: &Notice) -> Result<u64> {
+        self.push_indexed_output(Output::Notice(notice.clone()))
+    }
+
+    fn emit_report(&mut self, report: &[u8]) -> Result<()> {
+        self.push_output(Output::Report(report.into()))
+    }
+
+    fn emit_exception(&mut self, payload: &[u8]) -> Result<()> {
+        self.push_output(Output::Exception(payload.into()))
+    }
+
+    fn gio_request(&mut self, domain: u16, id: &[u8]) -> Result<GioResponse> {
+        self.current()?;
+
+        let responder = self
+            .gio_responder
+            .as_mut()
+            .ok_or(RollupError::InvalidRequest)?;
+        responder(domain, id)
+    }
+}
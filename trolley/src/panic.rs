@@ -0,0 +1,40 @@
+use crate::Rollup;
+use std::{cell::RefCell, panic::AssertUnwindSafe, sync::Once};
+
+thread_local! {
+    static MESSAGE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+// Chains onto the existing hook, so panics are still printed to the console.
+fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            MESSAGE.with(|m| *m.borrow_mut() = Some(info.to_string()));
+            previous(info);
+        }));
+    });
+}
+
+/// Runs `f`, emitting the message of any panic it raises as an exception before resuming the
+/// unwind.
+pub fn forward_panics<R: Rollup, T>(rollup: &mut R, f: impl FnOnce(&mut R) -> T) -> T {
+    install_hook();
+    // a panic caught earlier leaves its message behind, which `resume_unwind` would not replace
+    MESSAGE.with(|m| *m.borrow_mut() = None);
+
+    match std::panic::catch_unwind(AssertUnwindSafe(|| f(rollup))) {
+        Ok(t) => t,
+        Err(payload) => {
+            let message = MESSAGE
+                .with(|m| m.borrow_mut().take())
+                .unwrap_or_else(|| "dapp panicked".into());
+
+            // nothing left to do if the exception itself cannot be emitted
+            let _ = rollup.emit_exception(message.as_bytes());
+            std::panic::resume_unwind(payload)
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::Input;
+use alloy_primitives::{address, Address, Bytes, U256};
+use alloy_sol_types::SolValue;
+
+/// Portal addresses, defaulting to the rollups-contracts v2 deterministic deployments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Portals {
+    pub ether: Address,
+    pub erc20: Address,
+    pub erc721: Address,
+    pub erc1155_single: Address,
+    pub erc1155_batch: Address,
+}
+
+impl Default for Portals {
+    fn default() -> Self {
+        Self {
+            ether: address!("c70076a466789B595b50959cdc261227F0D70051"),
+            erc20: address!("c700D6aDd016eECd59d989C028214Eaa0fCC0051"),
+            erc721: address!("c700d52F5290e978e9CAe7D1E092935263b60051"),
+            erc1155_single: address!("c700A261279aFC6F755A3a67D86ae43E2eBD0051"),
+            erc1155_batch: address!("c700A2e5531E720a2434433b6ccf4c0eA2400051"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EtherDeposit {
+    pub sender: Address,
+    pub value: U256,
+    pub exec_layer_data: Bytes,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Erc20Deposit {
+    pub token: Address,
+    pub sender: Address,
+    pub value: U256,
+    pub exec_layer_data: Bytes,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Erc721Deposit {
+    pub token: Address,
+    pub sender: Address,
+    pub token_id: U256,
+    pub base_layer_data: Bytes,
+    pub exec_layer_data: Bytes,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Erc1155SingleDeposit {
+    pub token: Address,
+    pub sender: Address,
+    pub token_id: U256,
+    pub value: U256,
+    pub base_layer_data: Bytes,
+    pub exec_layer_data: Bytes,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Erc1155BatchDeposit {
+    pub token: Address,
+    pub sender: Address,
+    pub token_ids: Vec<U256>,
+    pub values: Vec<U256>,
+    pub base_layer_data: Bytes,
+    pub exec_layer_data: Bytes,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Deposit {
+    Ether(EtherDeposit),
+    Erc20(Erc20Deposit),
+    Erc721(Erc721Deposit),
+    Erc1155Single(Erc1155SingleDeposit),
+    Erc1155Batch(Erc1155BatchDeposit),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DepositError {
+    #[error("deposit payload too short")]
+    TooShort,
+
+    #[error("failed to decode deposit payload: {0}")]
+    Decode(#[from] alloy_sol_types::Error),
+}
+
+impl Portals {
+    /// Decodes `input` as a deposit, or returns `None` if it was not sent by one of the portals.
+    pub fn decode(&self, input: &Input) -> Result<Option<Deposit>, DepositError> {
+        let mut p = Packed(input.payload.as_ref());
+        let sender = input.msgSender;
+
+        let deposit = if sender == self.ether {
+            Deposit::Ether(EtherDeposit {
+                sender: p.address()?,
+                value: p.u256()?,
+                exec_layer_data: p.rest(),
+            })
+        } else if sender == self.erc20 {
+            Deposit::Erc20(Erc20Deposit {
+                token: p.address()?,
+                sender: p.address()?,
+                value: p.u256()?,
+                exec_layer_data: p.rest(),
+            })
+        } else if sender == self.erc721 {
+            let token = p.address()?;
+            let sender = p.address()?;
+            let token_id = p.u256()?;
+            let (base_layer_data, exec_layer_data) =
+                <(Bytes, Bytes)>::abi_decode_params(&p.rest(), true)?;
+
+            Deposit::Erc721(Erc721Deposit {
+                token,
+                sender,
+                token_id,
+                base_layer_data,
+                exec_layer_data,
+            })
+        } else if sender == self.erc1155_single {
+            let token = p.address()?;
+            let sender = p.address()?;
+            let token_id = p.u256()?;
+            let value = p.u256()?;
+            let (base_layer_data, exec_layer_data) =
+                <(Bytes, Bytes)>::abi_decode_params(&p.rest(), true)?;
+
+            Deposit::Erc1155Single(Erc1155SingleDeposit {
+                token,
+                sender,
+                token_id,
+                value,
+                base_layer_data,
+                exec_layer_data,
+            })
+        } else if sender == self.erc1155_batch {
+            let token = p.address()?;
+            let sender = p.address()?;
+            let (token_ids, values, base_layer_data, exec_layer_data) =
+                <(Vec<U256>, Vec<U256>, Bytes, Bytes)>::abi_decode_params(&p.rest(), true)?;
+
+            Deposit::Erc1155Batch(Erc1155BatchDeposit {
+                token,
+                sender,
+                token_ids,
+                values,
+                base_layer_data,
+                exec_layer_data,
+            })
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(deposit))
+    }
+}
+
+// Reader for the `abi.encodePacked` prefix of portal payloads.
+struct Packed<'a>(&'a [u8]);
+
+impl Packed<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], DepositError> {
+        if self.0.len() < n {
+            return Err(DepositError::TooShort);
+        }
+
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn address(&mut self) -> Result<Address, DepositError> {
+        Ok(Address::from_slice(self.take(20)?))
+    }
+
+    fn u256(&mut self) -> Result<U256, DepositError> {
+        Ok(U256::from_be_slice(self.take(32)?))
+    }
+
+    fn rest(&mut self) -> Bytes {
+        Bytes::copy_from_slice(std::mem::take(&mut self.0))
+    }
+}
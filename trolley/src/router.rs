@@ -0,0 +1,77 @@
+use crate::{Finish, Input, Rollup};
+use alloy_primitives::hex;
+use alloy_sol_types::SolCall;
+use std::collections::HashMap;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RouteError<E> {
+    #[error("payload has no selector")]
+    MissingSelector,
+
+    #[error("unknown selector: {}", hex::encode_prefixed(.0))]
+    UnknownSelector([u8; 4]),
+
+    #[error("failed to decode payload: {0}")]
+    Decode(alloy_sol_types::Error),
+
+    #[error("{0}")]
+    Handler(E),
+}
+
+type Handler<S, E> =
+    Box<dyn Fn(&mut S, &mut dyn Rollup, &Input, &[u8]) -> Result<Finish, RouteError<E>>>;
+
+/// Dispatches advance inputs on the 4-byte selector of their payload, decoding it into the
+/// `sol!` call registered for that selector.
+pub struct Router<S, E> {
+    routes: HashMap<[u8; 4], Handler<S, E>>,
+}
+
+impl<S, E> Default for Router<S, E> {
+    fn default() -> Self {
+        Self {
+            routes: HashMap::new(),
+        }
+    }
+}
+
+impl<S, E> Router<S, E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn route<C, F>(mut self, handler: F) -> Self
+    where
+        C: SolCall + 'static,
+        F: Fn(&mut S, &mut dyn Rollup, &Input, C) -> Result<Finish, E> + 'static,
+    {
+        self.routes.insert(
+            C::SELECTOR,
+            Box::new(move |state, rollup, input, payload| {
+                let call = C::abi_decode(payload, true).map_err(RouteError::Decode)?;
+                handler(state, rollup, input, call).map_err(RouteError::Handler)
+            }),
+        );
+        self
+    }
+
+    pub fn dispatch<R: Rollup>(
+        &self,
+        state: &mut S,
+        rollup: &mut R,
+        input: &Input,
+    ) -> Result<Finish, RouteError<E>> {
+        let payload = input.payload.as_ref();
+        let selector: [u8; 4] = payload
+            .get(..4)
+            .and_then(|s| s.try_into().ok())
+            .ok_or(RouteError::MissingSelector)?;
+
+        let handler = self
+            .routes
+            .get(&selector)
+            .ok_or(RouteError::UnknownSelector(selector))?;
+
+        handler(state, rollup, input, payload)
+    }
+}
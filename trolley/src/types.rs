@@ -1,5 +1,21 @@
-pub type Input = cartesi_rollups_contracts::inputs::Inputs::EvmAdvanceCall;
+pub use ::types::{DelegateCallVoucher, Input, Notice, Voucher};
 
-pub type Voucher = cartesi_rollups_contracts::outputs::Outputs::OutputsCalls;
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Finish {
+    #[default]
+    Accept,
+    Reject,
+}
 
-pub type Notice = cartesi_rollups_contracts::outputs::Outputs::OutputsCalls;
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Request {
+    Advance(Input),
+    Inspect(Vec<u8>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GioResponse {
+    pub code: u16,
+    pub data: Vec<u8>,
+}
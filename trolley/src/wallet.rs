@@ -0,0 +1,239 @@
+use crate::{portals::Deposit, Rollup, RollupError, Voucher};
+use alloy_primitives::{Address, Bytes, U256};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum WalletError {
+    #[error("insufficient balance")]
+    InsufficientBalance,
+
+    #[error("token not owned by sender")]
+    NotOwner,
+
+    #[error("balance overflow")]
+    Overflow,
+
+    #[error("batch has {ids} token ids but {values} values")]
+    BatchLengthMismatch { ids: usize, values: usize },
+
+    #[error(transparent)]
+    Rollup(#[from] RollupError),
+}
+
+pub type Result<T> = std::result::Result<T, WalletError>;
+
+/// Per-account ledger of the assets deposited into the application through the portals.
+#[derive(Clone, Debug, Default)]
+pub struct Wallet {
+    ether: HashMap<Address, U256>,
+    erc20: HashMap<(Address, Address), U256>,
+    erc721: HashMap<(Address, U256), Address>,
+    erc1155: HashMap<(Address, U256, Address), U256>,
+}
+
+impl Wallet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn deposit(&mut self, deposit: &Deposit) -> Result<()> {
+        match deposit {
+            Deposit::Ether(d) => credit(&mut self.ether, d.sender, d.value),
+
+            Deposit::Erc20(d) => credit(&mut self.erc20, (d.token, d.sender), d.value),
+
+            Deposit::Erc721(d) => {
+                self.erc721.insert((d.token, d.token_id), d.sender);
+                Ok(())
+            }
+
+            Deposit::Erc1155Single(d) => {
+                credit(&mut self.erc1155, (d.token, d.token_id, d.sender), d.value)
+            }
+
+            Deposit::Erc1155Batch(d) => {
+                if d.token_ids.len() != d.values.len() {
+                    return Err(WalletError::BatchLengthMismatch {
+                        ids: d.token_ids.len(),
+                        values: d.values.len(),
+                    });
+                }
+
+                // all or nothing: an overflow must not leave part of the batch credited
+                let mut balances = HashMap::new();
+                for (token_id, value) in d.token_ids.iter().zip(&d.values) {
+                    let key = (d.token, *token_id, d.sender);
+                    let balance = balances
+                        .entry(key)
+                        .or_insert_with(|| self.erc1155.get(&key).copied().unwrap_or_default());
+                    *balance = balance.checked_add(*value).ok_or(WalletError::Overflow)?;
+                }
+
+                self.erc1155
+                    .extend(balances.into_iter().filter(|(_, b)| !b.is_zero()));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn ether_balance(&self, owner: Address) -> U256 {
+        self.ether.get(&owner).copied().unwrap_or_default()
+    }
+
+    pub fn erc20_balance(&self, token: Address, owner: Address) -> U256 {
+        self.erc20.get(&(token, owner)).copied().unwrap_or_default()
+    }
+
+    pub fn erc721_owner(&self, token: Address, token_id: U256) -> Option<Address> {
+        self.erc721.get(&(token, token_id)).copied()
+    }
+
+    pub fn erc1155_balance(&self, token: Address, token_id: U256, owner: Address) -> U256 {
+        self.erc1155
+            .get(&(token, token_id, owner))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn transfer_ether(&mut self, from: Address, to: Address, value: U256) -> Result<()> {
+        debit(&mut self.ether, from, value)?;
+        credit(&mut self.ether, to, value)
+    }
+
+    pub fn transfer_erc20(
+        &mut self,
+        token: Address,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<()> {
+        debit(&mut self.erc20, (token, from), value)?;
+        credit(&mut self.erc20, (token, to), value)
+    }
+
+    pub fn transfer_erc721(
+        &mut self,
+        token: Address,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<()> {
+        let owner = self
+            .erc721
+            .get_mut(&(token, token_id))
+            .filter(|owner| **owner == from)
+            .ok_or(WalletError::NotOwner)?;
+
+        *owner = to;
+        Ok(())
+    }
+
+    pub fn transfer_erc1155(
+        &mut self,
+        token: Address,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        value: U256,
+    ) -> Result<()> {
+        debit(&mut self.erc1155, (token, token_id, from), value)?;
+        credit(&mut self.erc1155, (token, token_id, to), value)
+    }
+
+    // Withdrawals only touch the ledger once their voucher has been emitted.
+
+    pub fn withdraw_ether<R: Rollup>(
+        &mut self,
+        rollup: &mut R,
+        owner: Address,
+        value: U256,
+    ) -> Result<u64> {
+        check_balance(&self.ether, &owner, value)?;
+        let index = rollup.emit_voucher(&Voucher::ether_withdrawal(owner, value))?;
+        debit(&mut self.ether, owner, value)?;
+        Ok(index)
+    }
+
+    pub fn withdraw_erc20<R: Rollup>(
+        &mut self,
+        rollup: &mut R,
+        token: Address,
+        owner: Address,
+        value: U256,
+    ) -> Result<u64> {
+        check_balance(&self.erc20, &(token, owner), value)?;
+        let index = rollup.emit_voucher(&Voucher::erc20_withdrawal(token, owner, value))?;
+        debit(&mut self.erc20, (token, owner), value)?;
+        Ok(index)
+    }
+
+    pub fn withdraw_erc721<R: Rollup>(
+        &mut self,
+        rollup: &mut R,
+        app: Address,
+        token: Address,
+        owner: Address,
+        token_id: U256,
+    ) -> Result<u64> {
+        if self.erc721_owner(token, token_id) != Some(owner) {
+            return Err(WalletError::NotOwner);
+        }
+
+        let index =
+            rollup.emit_voucher(&Voucher::erc721_withdrawal(token, app, owner, token_id))?;
+        self.erc721.remove(&(token, token_id));
+        Ok(index)
+    }
+
+    pub fn withdraw_erc1155<R: Rollup>(
+        &mut self,
+        rollup: &mut R,
+        app: Address,
+        token: Address,
+        owner: Address,
+        token_id: U256,
+        value: U256,
+    ) -> Result<u64> {
+        check_balance(&self.erc1155, &(token, token_id, owner), value)?;
+        let index = rollup.emit_voucher(&Voucher::erc1155_withdrawal(
+            token,
+            app,
+            owner,
+            token_id,
+            value,
+            Bytes::new(),
+        ))?;
+        debit(&mut self.erc1155, (token, token_id, owner), value)?;
+        Ok(index)
+    }
+}
+
+fn credit<K: Eq + Hash>(balances: &mut HashMap<K, U256>, key: K, value: U256) -> Result<()> {
+    let balance = balances.entry(key).or_default();
+    *balance = balance.checked_add(value).ok_or(WalletError::Overflow)?;
+    Ok(())
+}
+
+fn debit<K: Eq + Hash>(balances: &mut HashMap<K, U256>, key: K, value: U256) -> Result<()> {
+    check_balance(balances, &key, value)?;
+
+    if let Entry::Occupied(mut balance) = balances.entry(key) {
+        *balance.get_mut() -= value;
+        if balance.get().is_zero() {
+            balance.remove();
+        }
+    }
+
+    Ok(())
+}
+
+fn check_balance<K: Eq + Hash>(balances: &HashMap<K, U256>, key: &K, value: U256) -> Result<()> {
+    let balance = balances.get(key).copied().unwrap_or_default();
+    if balance < value {
+        return Err(WalletError::InsufficientBalance);
+    }
+    Ok(())
+}
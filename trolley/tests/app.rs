@@ -0,0 +1,47 @@
+use alloy_primitives::Address;
+use trolley::{
+    mock::{input, RollupMock},
+    App, Finish, Input, Rollup,
+};
+
+#[derive(Default)]
+struct Counter {
+    count: usize,
+}
+
+impl App for Counter {
+    type Error = String;
+
+    fn advance<R: Rollup>(&mut self, _rollup: &mut R, input: Input) -> Result<Finish, String> {
+        if input.payload.is_empty() {
+            return Err("empty payload".into());
+        }
+
+        self.count += 1;
+        Ok(Finish::Accept)
+    }
+}
+
+#[test]
+fn handler_errors_become_reports_and_reject() {
+    let mut mock = RollupMock::with_inputs([
+        input(Address::ZERO, b"a"),
+        input(Address::ZERO, b""),
+        input(Address::ZERO, b"b"),
+    ]);
+    let mut app = Counter::default();
+
+    assert_eq!(trolley::run(&mut app, &mut mock), Ok(()));
+
+    assert_eq!(app.count, 2);
+    assert_eq!(mock.reports(), vec![b"empty payload".as_slice()]);
+    let finishes: Vec<_> = mock.processed().iter().map(|p| p.finish).collect();
+    assert_eq!(
+        finishes,
+        vec![
+            Some(Finish::Accept),
+            Some(Finish::Reject),
+            Some(Finish::Accept)
+        ]
+    );
+}
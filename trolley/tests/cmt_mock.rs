@@ -0,0 +1,91 @@
+//! Runs `RollupCmt` against the libcmt host mock, so needs `--features cmt-mock` and the libcmt
+//! sources, from LIBCMT_SRC or `libcmt-sys/vendor`. Without the `bindgen` feature of libcmt-sys,
+//! LIBCMT_VERSION must also name their release, to pick its committed bindings.
+//! `MockIo::install` sets the environment, so this binary must keep to a single test.
+#![cfg(feature = "cmt-mock")]
+
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::SolCall;
+use trolley::{
+    cmt::{RequestRef, RollupCmt},
+    cmt_mock::MockIo,
+    Finish, Input, Notice, Request, Rollup,
+};
+
+#[test]
+fn requests_round_trip_through_the_host_mock() {
+    let input = Input {
+        chainId: U256::from(31337),
+        appContract: Address::repeat_byte(0xaa),
+        msgSender: Address::repeat_byte(0xbb),
+        blockNumber: U256::from(7),
+        blockTimestamp: U256::from(1_700_000_000),
+        prevRandao: U256::from(42),
+        index: U256::ZERO,
+        payload: Bytes::from_static(b"hello"),
+    };
+    let borrowed_input = Input {
+        index: U256::from(1),
+        payload: Bytes::from_static(b"borrowed"),
+        ..input.clone()
+    };
+
+    let mut io = MockIo::new(concat!(env!("CARGO_TARGET_TMPDIR"), "/cmt-mock")).unwrap();
+    let advance = io.stage_advance(&input).unwrap();
+    let inspect = io.stage_inspect(b"query").unwrap();
+    let borrowed_advance = io.stage_advance(&borrowed_input).unwrap();
+
+    // SAFETY: this is the only test in the binary, so no other thread touches the environment
+    unsafe { io.install() };
+    let mut rollup = RollupCmt::try_new().unwrap();
+
+    assert_eq!(
+        rollup.next_request(Finish::Accept).unwrap(),
+        Request::Advance(input.clone())
+    );
+    let notice = Notice {
+        payload: input.payload.clone(),
+    };
+    assert_eq!(rollup.emit_notice(&notice), Ok(0));
+    rollup.emit_report(b"ack").unwrap();
+
+    // the borrowed requests are echoed straight from the rx buffer through the emitter
+    let (request, mut emitter) = rollup.next_request_ref(Finish::Accept).unwrap();
+    assert_eq!(request, RequestRef::Inspect(b"query"));
+    assert_eq!(request.to_owned(), Request::Inspect(b"query".to_vec()));
+    let RequestRef::Inspect(payload) = request else {
+        panic!("expected an inspect, got {:?}", request);
+    };
+    emitter.emit_report(payload).unwrap();
+
+    let (request, mut emitter) = rollup.next_request_ref(Finish::Accept).unwrap();
+    assert_eq!(request.to_owned(), Request::Advance(borrowed_input.clone()));
+    let RequestRef::Advance(borrowed) = request else {
+        panic!("expected an advance, got {:?}", request);
+    };
+    assert_eq!(borrowed.index, 1);
+    assert_eq!(borrowed.msg_sender, borrowed_input.msgSender);
+    let borrowed_notice = Notice {
+        payload: borrowed.payload.to_vec().into(),
+    };
+    assert_eq!(emitter.emit_notice(&borrowed_notice), Ok(1));
+    emitter.emit_report(borrowed.payload).unwrap();
+
+    assert!(rollup.next_request(Finish::Accept).is_err());
+
+    assert_eq!(io.outputs(advance).unwrap(), vec![notice.abi_encode()]);
+    assert_eq!(io.reports(advance).unwrap(), vec![b"ack".to_vec()]);
+    assert_eq!(io.exception(advance).unwrap(), None);
+
+    assert_eq!(io.outputs(inspect).unwrap(), Vec::<Vec<u8>>::new());
+    assert_eq!(io.reports(inspect).unwrap(), vec![b"query".to_vec()]);
+
+    assert_eq!(
+        io.outputs(borrowed_advance).unwrap(),
+        vec![borrowed_notice.abi_encode()]
+    );
+    assert_eq!(
+        io.reports(borrowed_advance).unwrap(),
+        vec![b"borrowed".to_vec()]
+    );
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "cmt-mock")]
+
+use alloy_primitives::hex;
+use trolley::keccak::{keccak256, Keccak};
+
+const INPUTS: &[&[u8]] = &[b"", b"abc", &[0xaa; 135], &[0xbb; 136], &[0xcc; 1000]];
+
+#[test]
+fn keccak256_matches_alloy() {
+    assert_eq!(
+        keccak256(b""),
+        hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+    );
+
+    for input in INPUTS {
+        assert_eq!(keccak256(input), alloy_primitives::keccak256(input).0);
+    }
+}
+
+#[test]
+fn incremental_keccak_matches_one_shot() {
+    for input in INPUTS {
+        for split in [0, input.len() / 3, input.len()] {
+            let mut keccak = Keccak::new();
+            keccak.update(&input[..split]);
+            keccak.update(&input[split..]);
+            assert_eq!(keccak.finalize(), keccak256(input));
+        }
+    }
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "cmt-mock")]
+
+use alloy_primitives::{keccak256, Address, Bytes, U256};
+use alloy_sol_types::SolCall;
+use trolley::{merkle::OutputsMerkle, Notice, Voucher};
+
+const HEIGHT: usize = 63;
+
+// The root of a tree of `HEIGHT` levels holding `leaves` and zeroes everywhere else.
+fn expected_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let mut level = leaves.to_vec();
+    let mut zero = [0; 32];
+
+    for _ in 0..HEIGHT {
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                let right = pair.get(1).unwrap_or(&zero);
+                keccak256([pair[0], *right].concat()).0
+            })
+            .collect();
+        zero = keccak256([zero, zero].concat()).0;
+    }
+
+    level.first().copied().unwrap_or(zero)
+}
+
+#[test]
+fn empty_tree_root() {
+    let merkle = OutputsMerkle::new();
+
+    assert_eq!(merkle.leaf_count(), 0);
+    assert_eq!(merkle.root_hash(), expected_root(&[]));
+}
+
+#[test]
+fn roots_match_a_reference_tree() {
+    let mut merkle = OutputsMerkle::new();
+    let mut leaves = Vec::new();
+
+    for i in 0..5u8 {
+        let leaf = keccak256([i]).0;
+        merkle.push_hash(&leaf).unwrap();
+        leaves.push(leaf);
+
+        assert_eq!(merkle.leaf_count(), leaves.len() as u64);
+        assert_eq!(merkle.root_hash(), expected_root(&leaves));
+    }
+
+    merkle.reset();
+    assert_eq!(merkle.leaf_count(), 0);
+    assert_eq!(merkle.root_hash(), expected_root(&[]));
+}
+
+#[test]
+fn outputs_are_pushed_as_the_hash_of_their_encoding() {
+    let voucher = Voucher {
+        destination: Address::repeat_byte(0xaa),
+        value: U256::from(1),
+        payload: Bytes::from_static(b"call"),
+    };
+    let notice = Notice {
+        payload: Bytes::from_static(b"hello"),
+    };
+
+    let mut merkle = OutputsMerkle::new();
+    merkle.push_voucher(&voucher).unwrap();
+    merkle.push_notice(&notice).unwrap();
+
+    let leaves = [
+        keccak256(voucher.abi_encode()).0,
+        keccak256(notice.abi_encode()).0,
+    ];
+    assert_eq!(merkle.root_hash(), expected_root(&leaves));
+}
@@ -0,0 +1,145 @@
+use alloy_primitives::{Address, Bytes, U256};
+use trolley::{
+    mock::{input, RollupMock},
+    DelegateCallVoucher, Finish, GioResponse, Notice, Request, Rollup, RollupError, Voucher,
+};
+
+fn notice(payload: &[u8]) -> Notice {
+    Notice {
+        payload: payload.to_vec().into(),
+    }
+}
+
+#[test]
+fn rejected_outputs_are_dropped_and_their_indices_reused() {
+    let mut mock =
+        RollupMock::with_inputs([input(Address::ZERO, b"a"), input(Address::ZERO, b"b")]);
+
+    mock.next_request(Finish::Accept).unwrap();
+    assert_eq!(mock.emit_notice(&notice(b"rejected")).unwrap(), 0);
+    mock.emit_report(b"kept").unwrap();
+
+    mock.next_request(Finish::Reject).unwrap();
+    assert_eq!(mock.emit_notice(&notice(b"accepted")).unwrap(), 0);
+
+    assert_eq!(
+        mock.next_request(Finish::Accept),
+        Err(RollupError::NoMoreRequests)
+    );
+    assert_eq!(mock.notices(), vec![&notice(b"accepted")]);
+    assert_eq!(mock.reports(), vec![b"kept".as_slice()]);
+    assert_eq!(mock.processed()[0].outputs.len(), 2);
+}
+
+#[test]
+fn inspect_cannot_emit_vouchers_or_notices() {
+    let mut mock = RollupMock::new();
+    mock.push_inspect(b"query");
+
+    assert_eq!(
+        mock.next_request(Finish::Accept),
+        Ok(Request::Inspect(b"query".to_vec()))
+    );
+    assert_eq!(
+        mock.emit_notice(&notice(b"n")),
+        Err(RollupError::InvalidRequest)
+    );
+    assert_eq!(
+        mock.emit_voucher(&Voucher::ether_withdrawal(Address::ZERO, U256::from(1))),
+        Err(RollupError::InvalidRequest)
+    );
+    mock.emit_report(b"answer").unwrap();
+
+    assert_eq!(
+        mock.next_request(Finish::Accept),
+        Err(RollupError::NoMoreRequests)
+    );
+    assert!(mock.notices().is_empty());
+    assert!(mock.outputs().next().is_none());
+    assert_eq!(mock.reports(), vec![b"answer".as_slice()]);
+}
+
+#[test]
+fn outputs_of_an_unfinished_request_are_not_accepted_yet() {
+    let mut mock = RollupMock::with_inputs([input(Address::ZERO, b"a")]);
+
+    mock.next_request(Finish::Accept).unwrap();
+    mock.emit_notice(&Notice {
+        payload: Bytes::new(),
+    })
+    .unwrap();
+
+    assert!(mock.notices().is_empty());
+}
+
+#[test]
+fn gio_requests_go_to_the_responder() {
+    let mut mock = RollupMock::with_inputs([input(Address::ZERO, b"a")]);
+    mock.set_gio_responder(|domain, id| {
+        Ok(GioResponse {
+            code: domain + 1,
+            data: [id, b"!"].concat(),
+        })
+    });
+
+    // there is no request to ask on behalf of yet
+    assert_eq!(
+        mock.gio_request(0x10, b"id"),
+        Err(RollupError::InvalidRequest)
+    );
+
+    mock.next_request(Finish::Accept).unwrap();
+    assert_eq!(
+        mock.gio_request(0x10, b"id"),
+        Ok(GioResponse {
+            code: 0x11,
+            data: b"id!".to_vec(),
+        })
+    );
+}
+
+#[test]
+fn gio_requests_fail_without_a_responder() {
+    let mut mock = RollupMock::with_inputs([input(Address::ZERO, b"a")]);
+    mock.next_request(Finish::Accept).unwrap();
+
+    assert_eq!(
+        mock.gio_request(0x10, b"id"),
+        Err(RollupError::InvalidRequest)
+    );
+}
+
+#[test]
+fn gio_responder_errors_are_returned() {
+    let mut mock = RollupMock::new();
+    mock.push_inspect(b"query");
+    mock.set_gio_responder(|_, _| Err(RollupError::BufferTooSmall));
+    mock.next_request(Finish::Accept).unwrap();
+
+    assert_eq!(
+        mock.gio_request(0x10, b"id"),
+        Err(RollupError::BufferTooSmall)
+    );
+}
+
+#[test]
+fn delegate_call_vouchers_share_the_output_indices() {
+    let voucher = DelegateCallVoucher {
+        destination: Address::repeat_byte(0xaa),
+        payload: Bytes::from_static(b"call"),
+    };
+    let mut mock =
+        RollupMock::with_inputs([input(Address::ZERO, b"a"), input(Address::ZERO, b"b")]);
+
+    mock.next_request(Finish::Accept).unwrap();
+    assert_eq!(mock.emit_notice(&notice(b"first")), Ok(0));
+    assert_eq!(mock.emit_delegate_call_voucher(&voucher), Ok(1));
+
+    mock.next_request(Finish::Accept).unwrap();
+    assert_eq!(mock.emit_delegate_call_voucher(&voucher), Ok(2));
+
+    // rejected along with the second input
+    mock.next_request(Finish::Reject).unwrap_err();
+    assert_eq!(mock.delegate_call_vouchers(), vec![&voucher]);
+    assert!(mock.vouchers().is_empty());
+}
@@ -0,0 +1,44 @@
+use alloy_primitives::Address;
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use trolley::{
+    mock::{input, RollupMock},
+    panic::forward_panics,
+    Finish, Rollup,
+};
+
+// A mock in the middle of an advance request, so an exception can be emitted.
+fn advancing() -> RollupMock {
+    let mut mock = RollupMock::with_inputs([input(Address::ZERO, b"a")]);
+    mock.next_request(Finish::Accept).unwrap();
+    mock
+}
+
+#[test]
+fn panics_are_emitted_as_exceptions() {
+    let mut mock = advancing();
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        forward_panics(&mut mock, |_| panic!("boom"))
+    }));
+    assert!(result.is_err());
+
+    let exception = String::from_utf8(mock.exception().unwrap().to_vec()).unwrap();
+    assert!(exception.contains("boom"), "{}", exception);
+}
+
+#[test]
+fn stale_panic_messages_are_not_forwarded() {
+    let mut mock = advancing();
+
+    // a panic caught without leaving `f` still goes through the hook
+    let caught = forward_panics(&mut mock, |_| catch_unwind(|| panic!("caught")).is_err());
+    assert!(caught);
+    assert_eq!(mock.exception(), None);
+
+    // while resuming an unwind does not
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        forward_panics(&mut mock, |_| resume_unwind(Box::new("resumed")))
+    }));
+    assert!(result.is_err());
+    assert_eq!(mock.exception(), Some(b"dapp panicked".as_slice()));
+}
@@ -0,0 +1,157 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::SolValue;
+use trolley::{mock::input, portals::*};
+
+const TOKEN: Address = Address::repeat_byte(0x11);
+const ALICE: Address = Address::repeat_byte(0xaa);
+
+// `abi.encodePacked` of addresses and uint256s is their raw 20 and 32 bytes, back to back.
+fn packed(parts: &[&[u8]]) -> Vec<u8> {
+    parts.concat()
+}
+
+fn word(n: u64) -> [u8; 32] {
+    U256::from(n).to_be_bytes()
+}
+
+#[test]
+fn decodes_ether_deposits() {
+    let portals = Portals::default();
+    let payload = packed(&[ALICE.as_slice(), &word(5), b"exec"]);
+
+    assert_eq!(
+        portals.decode(&input(portals.ether, &payload)).unwrap(),
+        Some(Deposit::Ether(EtherDeposit {
+            sender: ALICE,
+            value: U256::from(5),
+            exec_layer_data: Bytes::from_static(b"exec"),
+        }))
+    );
+}
+
+#[test]
+fn decodes_erc20_deposits() {
+    let portals = Portals::default();
+    let payload = packed(&[TOKEN.as_slice(), ALICE.as_slice(), &word(7), b"exec"]);
+
+    assert_eq!(
+        portals.decode(&input(portals.erc20, &payload)).unwrap(),
+        Some(Deposit::Erc20(Erc20Deposit {
+            token: TOKEN,
+            sender: ALICE,
+            value: U256::from(7),
+            exec_layer_data: Bytes::from_static(b"exec"),
+        }))
+    );
+}
+
+#[test]
+fn decodes_erc721_deposits() {
+    let portals = Portals::default();
+    let data = (Bytes::from_static(b"base"), Bytes::from_static(b"exec")).abi_encode_params();
+    let payload = packed(&[TOKEN.as_slice(), ALICE.as_slice(), &word(42), &data]);
+
+    assert_eq!(
+        portals.decode(&input(portals.erc721, &payload)).unwrap(),
+        Some(Deposit::Erc721(Erc721Deposit {
+            token: TOKEN,
+            sender: ALICE,
+            token_id: U256::from(42),
+            base_layer_data: Bytes::from_static(b"base"),
+            exec_layer_data: Bytes::from_static(b"exec"),
+        }))
+    );
+}
+
+#[test]
+fn decodes_erc1155_single_deposits() {
+    let portals = Portals::default();
+    let data = (Bytes::from_static(b"base"), Bytes::from_static(b"exec")).abi_encode_params();
+    let payload = packed(&[
+        TOKEN.as_slice(),
+        ALICE.as_slice(),
+        &word(1),
+        &word(3),
+        &data,
+    ]);
+
+    assert_eq!(
+        portals
+            .decode(&input(portals.erc1155_single, &payload))
+            .unwrap(),
+        Some(Deposit::Erc1155Single(Erc1155SingleDeposit {
+            token: TOKEN,
+            sender: ALICE,
+            token_id: U256::from(1),
+            value: U256::from(3),
+            base_layer_data: Bytes::from_static(b"base"),
+            exec_layer_data: Bytes::from_static(b"exec"),
+        }))
+    );
+}
+
+#[test]
+fn decodes_erc1155_batch_deposits() {
+    let portals = Portals::default();
+    let ids = vec![U256::from(1), U256::from(2)];
+    let values = vec![U256::from(10), U256::from(20)];
+    let data = (
+        ids.clone(),
+        values.clone(),
+        Bytes::from_static(b"base"),
+        Bytes::from_static(b"exec"),
+    )
+        .abi_encode_params();
+    let payload = packed(&[TOKEN.as_slice(), ALICE.as_slice(), &data]);
+
+    assert_eq!(
+        portals
+            .decode(&input(portals.erc1155_batch, &payload))
+            .unwrap(),
+        Some(Deposit::Erc1155Batch(Erc1155BatchDeposit {
+            token: TOKEN,
+            sender: ALICE,
+            token_ids: ids,
+            values,
+            base_layer_data: Bytes::from_static(b"base"),
+            exec_layer_data: Bytes::from_static(b"exec"),
+        }))
+    );
+}
+
+#[test]
+fn ignores_inputs_not_sent_by_a_portal() {
+    let portals = Portals::default();
+    let payload = packed(&[ALICE.as_slice(), &word(5)]);
+
+    assert_eq!(portals.decode(&input(ALICE, &payload)).unwrap(), None);
+}
+
+#[test]
+fn rejects_truncated_packed_fields() {
+    let portals = Portals::default();
+
+    // the value stops one byte short
+    let payload = packed(&[ALICE.as_slice(), &word(5)[..31]]);
+    assert!(matches!(
+        portals.decode(&input(portals.ether, &payload)),
+        Err(DepositError::TooShort)
+    ));
+
+    let payload = packed(&[TOKEN.as_slice(), &ALICE[..10]]);
+    assert!(matches!(
+        portals.decode(&input(portals.erc1155_batch, &payload)),
+        Err(DepositError::TooShort)
+    ));
+}
+
+#[test]
+fn rejects_malformed_abi_tails() {
+    let portals = Portals::default();
+    let payload = packed(&[TOKEN.as_slice(), ALICE.as_slice(), &word(42), b"not abi"]);
+
+    assert!(matches!(
+        portals.decode(&input(portals.erc721, &payload)),
+        Err(DepositError::Decode(_))
+    ));
+}
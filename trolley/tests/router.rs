@@ -0,0 +1,87 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall};
+use trolley::{
+    mock::{input, RollupMock},
+    router::{RouteError, Router},
+    Finish, Input, Rollup,
+};
+
+sol! {
+    function increment(uint256 by) external;
+    function reset() external;
+    function unrouted() external;
+}
+
+fn router() -> Router<U256, String> {
+    Router::new()
+        .route(
+            |count: &mut U256, _: &mut dyn Rollup, _: &Input, call: incrementCall| {
+                *count += call.by;
+                Ok(Finish::Accept)
+            },
+        )
+        .route(
+            |_: &mut U256, _: &mut dyn Rollup, _: &Input, _: resetCall| {
+                Err("cannot reset".to_string())
+            },
+        )
+}
+
+fn dispatch(count: &mut U256, payload: Vec<u8>) -> Result<Finish, RouteError<String>> {
+    router().dispatch(
+        count,
+        &mut RollupMock::new(),
+        &input(Address::ZERO, &payload),
+    )
+}
+
+#[test]
+fn dispatches_decoded_calls_by_selector() {
+    let mut count = U256::from(1);
+    let call = incrementCall { by: U256::from(2) }.abi_encode();
+
+    let finish = dispatch(&mut count, call).unwrap();
+
+    assert_eq!(finish, Finish::Accept);
+    assert_eq!(count, U256::from(3));
+}
+
+#[test]
+fn handler_errors_are_wrapped() {
+    let result = dispatch(&mut U256::default(), resetCall {}.abi_encode());
+
+    assert!(matches!(result, Err(RouteError::Handler(e)) if e == "cannot reset"));
+}
+
+#[test]
+fn rejects_unknown_selectors() {
+    let result = dispatch(&mut U256::default(), unroutedCall {}.abi_encode());
+
+    let err = result.unwrap_err();
+    assert!(matches!(err, RouteError::UnknownSelector(s) if s == unroutedCall::SELECTOR));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "unknown selector: 0x{}",
+            alloy_primitives::hex::encode(unroutedCall::SELECTOR)
+        )
+    );
+}
+
+#[test]
+fn rejects_payloads_shorter_than_a_selector() {
+    let result = dispatch(&mut U256::default(), vec![1, 2, 3]);
+
+    assert!(matches!(result, Err(RouteError::MissingSelector)));
+}
+
+#[test]
+fn rejects_calls_that_fail_to_decode() {
+    // the selector is right, but the argument is cut short
+    let mut payload = incrementCall { by: U256::from(2) }.abi_encode();
+    payload.truncate(20);
+
+    let result = dispatch(&mut U256::default(), payload);
+
+    assert!(matches!(result, Err(RouteError::Decode(_))));
+}
@@ -0,0 +1,193 @@
+use alloy_primitives::{Address, Bytes, U256};
+use trolley::{
+    mock::{input, RollupMock},
+    portals::*,
+    wallet::{Wallet, WalletError},
+    Finish, Rollup, RollupError, Voucher,
+};
+
+const TOKEN: Address = Address::repeat_byte(0x11);
+const APP: Address = Address::repeat_byte(0xbb);
+const ALICE: Address = Address::repeat_byte(0xaa);
+const BOB: Address = Address::repeat_byte(0xb0);
+
+// A mock in the middle of an advance request, so outputs can be emitted.
+fn advancing() -> RollupMock {
+    let mut mock = RollupMock::with_inputs([input(ALICE, b"")]);
+    mock.next_request(Finish::Accept).unwrap();
+    mock
+}
+
+fn ether(sender: Address, value: U256) -> Deposit {
+    Deposit::Ether(EtherDeposit {
+        sender,
+        value,
+        exec_layer_data: Bytes::new(),
+    })
+}
+
+fn erc721(token_id: u64) -> Deposit {
+    Deposit::Erc721(Erc721Deposit {
+        token: TOKEN,
+        sender: ALICE,
+        token_id: U256::from(token_id),
+        base_layer_data: Bytes::new(),
+        exec_layer_data: Bytes::new(),
+    })
+}
+
+fn erc1155_batch(token_ids: &[u64], values: &[U256]) -> Deposit {
+    Deposit::Erc1155Batch(Erc1155BatchDeposit {
+        token: TOKEN,
+        sender: ALICE,
+        token_ids: token_ids.iter().map(|id| U256::from(*id)).collect(),
+        values: values.to_vec(),
+        base_layer_data: Bytes::new(),
+        exec_layer_data: Bytes::new(),
+    })
+}
+
+#[test]
+fn transfers_cannot_overdraw() {
+    let mut wallet = Wallet::new();
+    wallet.deposit(&ether(ALICE, U256::from(5))).unwrap();
+
+    assert_eq!(
+        wallet.transfer_ether(ALICE, BOB, U256::from(6)),
+        Err(WalletError::InsufficientBalance)
+    );
+    assert_eq!(wallet.ether_balance(ALICE), U256::from(5));
+    assert_eq!(wallet.ether_balance(BOB), U256::ZERO);
+
+    wallet.transfer_ether(ALICE, BOB, U256::from(5)).unwrap();
+    assert_eq!(wallet.ether_balance(ALICE), U256::ZERO);
+    assert_eq!(wallet.ether_balance(BOB), U256::from(5));
+}
+
+#[test]
+fn only_the_owner_moves_an_erc721() {
+    let mut wallet = Wallet::new();
+    wallet.deposit(&erc721(1)).unwrap();
+
+    assert_eq!(
+        wallet.transfer_erc721(TOKEN, BOB, BOB, U256::from(1)),
+        Err(WalletError::NotOwner)
+    );
+    assert_eq!(
+        wallet.withdraw_erc721(&mut advancing(), APP, TOKEN, BOB, U256::from(1)),
+        Err(WalletError::NotOwner)
+    );
+    assert_eq!(wallet.erc721_owner(TOKEN, U256::from(1)), Some(ALICE));
+
+    wallet
+        .transfer_erc721(TOKEN, ALICE, BOB, U256::from(1))
+        .unwrap();
+    assert_eq!(wallet.erc721_owner(TOKEN, U256::from(1)), Some(BOB));
+}
+
+#[test]
+fn deposits_cannot_overflow() {
+    let mut wallet = Wallet::new();
+    wallet.deposit(&ether(ALICE, U256::MAX)).unwrap();
+
+    assert_eq!(
+        wallet.deposit(&ether(ALICE, U256::from(1))),
+        Err(WalletError::Overflow)
+    );
+    assert_eq!(wallet.ether_balance(ALICE), U256::MAX);
+
+    wallet.deposit(&ether(BOB, U256::from(1))).unwrap();
+    assert_eq!(
+        wallet.transfer_ether(ALICE, BOB, U256::MAX),
+        Err(WalletError::Overflow)
+    );
+}
+
+#[test]
+fn batch_deposits_are_all_or_nothing() {
+    let mut wallet = Wallet::new();
+    wallet.deposit(&erc1155_batch(&[2], &[U256::MAX])).unwrap();
+
+    // id 1 fits, but id 2 overflows, so id 1 must not be credited either
+    assert_eq!(
+        wallet.deposit(&erc1155_batch(&[1, 2], &[U256::from(3), U256::from(1)])),
+        Err(WalletError::Overflow)
+    );
+    assert_eq!(
+        wallet.erc1155_balance(TOKEN, U256::from(1), ALICE),
+        U256::ZERO
+    );
+    assert_eq!(
+        wallet.erc1155_balance(TOKEN, U256::from(2), ALICE),
+        U256::MAX
+    );
+
+    // repeated ids add up
+    wallet
+        .deposit(&erc1155_batch(&[1, 1], &[U256::from(3), U256::from(4)]))
+        .unwrap();
+    assert_eq!(
+        wallet.erc1155_balance(TOKEN, U256::from(1), ALICE),
+        U256::from(7)
+    );
+}
+
+#[test]
+fn batch_deposits_need_a_value_per_id() {
+    let mut wallet = Wallet::new();
+
+    assert_eq!(
+        wallet.deposit(&erc1155_batch(&[1, 2], &[U256::from(3)])),
+        Err(WalletError::BatchLengthMismatch { ids: 2, values: 1 })
+    );
+    assert_eq!(
+        wallet.erc1155_balance(TOKEN, U256::from(1), ALICE),
+        U256::ZERO
+    );
+}
+
+#[test]
+fn withdrawals_emit_a_voucher_and_debit() {
+    let mut wallet = Wallet::new();
+    wallet.deposit(&ether(ALICE, U256::from(5))).unwrap();
+    let mut rollup = advancing();
+
+    assert_eq!(
+        wallet.withdraw_ether(&mut rollup, ALICE, U256::from(6)),
+        Err(WalletError::InsufficientBalance)
+    );
+    assert_eq!(
+        wallet.withdraw_ether(&mut rollup, ALICE, U256::from(2)),
+        Ok(0)
+    );
+    assert_eq!(wallet.ether_balance(ALICE), U256::from(3));
+
+    rollup.next_request(Finish::Accept).unwrap_err();
+    assert_eq!(
+        rollup.vouchers(),
+        vec![&Voucher::ether_withdrawal(ALICE, U256::from(2))]
+    );
+}
+
+#[test]
+fn withdrawals_keep_the_balance_if_the_voucher_is_not_emitted() {
+    let mut wallet = Wallet::new();
+    wallet.deposit(&ether(ALICE, U256::from(5))).unwrap();
+    wallet.deposit(&erc721(1)).unwrap();
+
+    // vouchers cannot be emitted while inspecting
+    let mut rollup = RollupMock::new();
+    rollup.push_inspect(b"");
+    rollup.next_request(Finish::Accept).unwrap();
+
+    assert_eq!(
+        wallet.withdraw_ether(&mut rollup, ALICE, U256::from(5)),
+        Err(WalletError::Rollup(RollupError::InvalidRequest))
+    );
+    assert_eq!(
+        wallet.withdraw_erc721(&mut rollup, APP, TOKEN, ALICE, U256::from(1)),
+        Err(WalletError::Rollup(RollupError::InvalidRequest))
+    );
+    assert_eq!(wallet.ether_balance(ALICE), U256::from(5));
+    assert_eq!(wallet.erc721_owner(TOKEN, U256::from(1)), Some(ALICE));
+}
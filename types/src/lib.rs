@@ -1,4 +1,5 @@
-use alloy_sol_types::sol;
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::{sol, SolCall};
 
 // TODO create crate with alloy type definitions in rollup-contracts once this is merged:
 // https://github.com/foundry-rs/foundry/pull/7919
@@ -24,8 +25,128 @@ sol! {
         uint256 value,
         bytes calldata payload
     ) external;
+
+    #[derive(Debug, PartialEq, Eq)]
+    function DelegateCallVoucher(
+        address destination,
+        bytes calldata payload
+    ) external;
 }
 
 pub type Input = EvmAdvanceCall;
 pub type Voucher = VoucherCall;
 pub type Notice = NoticeCall;
+pub type DelegateCallVoucher = DelegateCallVoucherCall;
+
+sol! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+    }
+
+    interface IERC721 {
+        function safeTransferFrom(address from, address to, uint256 tokenId) external;
+    }
+
+    interface IERC1155 {
+        function safeTransferFrom(
+            address from,
+            address to,
+            uint256 id,
+            uint256 value,
+            bytes data
+        ) external;
+
+        function safeBatchTransferFrom(
+            address from,
+            address to,
+            uint256[] ids,
+            uint256[] values,
+            bytes data
+        ) external;
+    }
+}
+
+// Withdrawals move assets held by the application contract, which executes the voucher.
+impl Voucher {
+    pub fn ether_withdrawal(receiver: Address, value: U256) -> Self {
+        Self {
+            destination: receiver,
+            value,
+            payload: Bytes::new(),
+        }
+    }
+
+    pub fn erc20_withdrawal(token: Address, receiver: Address, value: U256) -> Self {
+        Self::call(
+            token,
+            IERC20::transferCall {
+                to: receiver,
+                value,
+            },
+        )
+    }
+
+    pub fn erc721_withdrawal(
+        token: Address,
+        app: Address,
+        receiver: Address,
+        token_id: U256,
+    ) -> Self {
+        Self::call(
+            token,
+            IERC721::safeTransferFromCall {
+                from: app,
+                to: receiver,
+                tokenId: token_id,
+            },
+        )
+    }
+
+    pub fn erc1155_withdrawal(
+        token: Address,
+        app: Address,
+        receiver: Address,
+        token_id: U256,
+        value: U256,
+        data: Bytes,
+    ) -> Self {
+        Self::call(
+            token,
+            IERC1155::safeTransferFromCall {
+                from: app,
+                to: receiver,
+                id: token_id,
+                value,
+                data,
+            },
+        )
+    }
+
+    pub fn erc1155_batch_withdrawal(
+        token: Address,
+        app: Address,
+        receiver: Address,
+        token_ids: Vec<U256>,
+        values: Vec<U256>,
+        data: Bytes,
+    ) -> Self {
+        Self::call(
+            token,
+            IERC1155::safeBatchTransferFromCall {
+                from: app,
+                to: receiver,
+                ids: token_ids,
+                values,
+                data,
+            },
+        )
+    }
+
+    fn call<C: SolCall>(destination: Address, call: C) -> Self {
+        Self {
+            destination,
+            value: U256::ZERO,
+            payload: call.abi_encode().into(),
+        }
+    }
+}
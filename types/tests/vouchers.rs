@@ -0,0 +1,162 @@
+use alloy_primitives::{hex, keccak256, Address, Bytes, U256};
+use alloy_sol_types::SolCall;
+use types::{DelegateCallVoucher, Voucher};
+
+const TOKEN: Address = Address::repeat_byte(0x11);
+const APP: Address = Address::repeat_byte(0xbb);
+const ALICE: Address = Address::repeat_byte(0xaa);
+
+fn selector(signature: &str) -> [u8; 4] {
+    keccak256(signature)[..4].try_into().unwrap()
+}
+
+fn word(n: u64) -> [u8; 32] {
+    U256::from(n).to_be_bytes()
+}
+
+fn address_word(address: Address) -> [u8; 32] {
+    address.into_word().0
+}
+
+#[test]
+fn ether_withdrawals_carry_value_and_no_payload() {
+    let voucher = Voucher::ether_withdrawal(ALICE, U256::from(5));
+
+    assert_eq!(voucher.destination, ALICE);
+    assert_eq!(voucher.value, U256::from(5));
+    assert!(voucher.payload.is_empty());
+}
+
+#[test]
+fn erc20_withdrawals_call_transfer() {
+    let voucher = Voucher::erc20_withdrawal(TOKEN, ALICE, U256::from(7));
+
+    assert_eq!(selector("transfer(address,uint256)"), hex!("a9059cbb"));
+    let expected = [hex!("a9059cbb").as_slice(), &address_word(ALICE), &word(7)].concat();
+
+    assert_eq!(voucher.destination, TOKEN);
+    assert_eq!(voucher.value, U256::ZERO);
+    assert_eq!(voucher.payload.as_ref(), expected);
+}
+
+#[test]
+fn erc721_withdrawals_call_safe_transfer_from_the_app() {
+    let voucher = Voucher::erc721_withdrawal(TOKEN, APP, ALICE, U256::from(42));
+
+    assert_eq!(
+        selector("safeTransferFrom(address,address,uint256)"),
+        hex!("42842e0e")
+    );
+    let expected = [
+        hex!("42842e0e").as_slice(),
+        &address_word(APP),
+        &address_word(ALICE),
+        &word(42),
+    ]
+    .concat();
+
+    assert_eq!(voucher.destination, TOKEN);
+    assert_eq!(voucher.value, U256::ZERO);
+    assert_eq!(voucher.payload.as_ref(), expected);
+}
+
+#[test]
+fn erc1155_withdrawals_call_safe_transfer_from_the_app() {
+    let voucher = Voucher::erc1155_withdrawal(
+        TOKEN,
+        APP,
+        ALICE,
+        U256::from(1),
+        U256::from(3),
+        Bytes::from_static(b"hi"),
+    );
+
+    assert_eq!(
+        selector("safeTransferFrom(address,address,uint256,uint256,bytes)"),
+        hex!("f242432a")
+    );
+    let mut data = [0; 32];
+    data[..2].copy_from_slice(b"hi");
+    let expected = [
+        hex!("f242432a").as_slice(),
+        &address_word(APP),
+        &address_word(ALICE),
+        &word(1),
+        &word(3),
+        &word(5 * 32), // offset of `data`
+        &word(2),
+        &data,
+    ]
+    .concat();
+
+    assert_eq!(voucher.destination, TOKEN);
+    assert_eq!(voucher.value, U256::ZERO);
+    assert_eq!(voucher.payload.as_ref(), expected);
+}
+
+#[test]
+fn erc1155_batch_withdrawals_call_safe_batch_transfer_from_the_app() {
+    let voucher = Voucher::erc1155_batch_withdrawal(
+        TOKEN,
+        APP,
+        ALICE,
+        vec![U256::from(1), U256::from(2)],
+        vec![U256::from(10), U256::from(20)],
+        Bytes::new(),
+    );
+
+    assert_eq!(
+        selector("safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)"),
+        hex!("2eb2c2d6")
+    );
+    let expected = [
+        hex!("2eb2c2d6").as_slice(),
+        &address_word(APP),
+        &address_word(ALICE),
+        &word(5 * 32),  // offset of `ids`
+        &word(8 * 32),  // offset of `values`
+        &word(11 * 32), // offset of `data`
+        &word(2),
+        &word(1),
+        &word(2),
+        &word(2),
+        &word(10),
+        &word(20),
+        &word(0),
+    ]
+    .concat();
+
+    assert_eq!(voucher.destination, TOKEN);
+    assert_eq!(voucher.value, U256::ZERO);
+    assert_eq!(voucher.payload.as_ref(), expected);
+}
+
+#[test]
+fn delegate_call_vouchers_encode_as_their_own_output() {
+    let voucher = DelegateCallVoucher {
+        destination: TOKEN,
+        payload: Bytes::from_static(b"call"),
+    };
+
+    assert_eq!(
+        selector("DelegateCallVoucher(address,bytes)"),
+        DelegateCallVoucher::SELECTOR
+    );
+    assert_ne!(DelegateCallVoucher::SELECTOR, Voucher::SELECTOR);
+
+    let mut data = [0; 32];
+    data[..4].copy_from_slice(b"call");
+    let expected = [
+        DelegateCallVoucher::SELECTOR.as_slice(),
+        &address_word(TOKEN),
+        &word(0x40),
+        &word(4),
+        &data,
+    ]
+    .concat();
+    assert_eq!(voucher.abi_encode(), expected);
+    assert_eq!(
+        DelegateCallVoucher::abi_decode(&expected, true).unwrap(),
+        voucher
+    );
+}